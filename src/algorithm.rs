@@ -1,32 +1,118 @@
 use crate::point::*;
-use crate::simplex::*;
-use crate::search_space::*;
-use priority_queue::PriorityQueue;
-use ordered_float::OrderedFloat;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use crate::search_space::Triangulation;
+use crate::stop::*;
+use crate::evaluation::*;
+use crate::observer::*;
+use crate::scoring::*;
+use crate::schedule::*;
 use num_traits::Float;
-use std::rc::Rc;
+use std::ops::ControlFlow;
+
+/// a function, owned or borrowed for the given lifetime, that can be evaluated on coordinates
+pub(crate) type Objective<'f_lifetime, CoordFloat, Output> = Box<dyn FnMut(&[CoordFloat]) -> Output + 'f_lifetime>;
 
 /// Stores the parameters and current state of a search.
 ///
 /// - `ValueFloat` is the float type used to represent the evaluations (such as f64)
 /// - `CoordFloat` is the float type used to represent the coordinates (such as f32)
-pub struct Optimizer<'f_lifetime, CoordFloat: Float, ValueFloat: Float>
+///
+/// The optimizer can either borrow its function (`Optimizer::new(&f, ...)`) or own it (`Optimizer::new(f, ...)`),
+/// an optimizer that owns its function is an `Optimizer<'static, ...>` which can be stored or returned freely.
+/// To move a search to another thread, send its state (see `into_state`) and rebuild the optimizer there with `from_state`.
+///
+/// ```rust
+/// # use simplers_optimization::Optimizer;
+/// # fn main() {
+/// struct Service
+/// {
+///     optimizer: Optimizer<'static, f64, f64>
+/// }
+///
+/// fn make_optimizer(target: f64) -> Optimizer<'static, f64, f64>
+/// {
+///     // the closure, and the data it captures, are moved into the optimizer
+///     let f = move |v:&[f64]| (v[0] - target).powi(2) + v[1].powi(2);
+///     let input_interval = vec![(-10., 10.), (-20., 20.)];
+///     let should_minimize = true;
+///     Optimizer::new(f, &input_interval, should_minimize)
+/// }
+///
+/// let mut service = Service { optimizer: make_optimizer(3.) };
+/// let (min_value, coordinates) = service.optimizer.nth(30).unwrap();
+/// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+///
+/// // moves the search to another thread, where the optimizer is rebuilt around the function
+/// let state = service.optimizer.into_state();
+/// let f = |v:&[f64]| (v[0] - 3.).powi(2) + v[1].powi(2);
+/// let worker = std::thread::spawn(move || Optimizer::from_state(f, state).nth(30));
+/// let (resumed_value, _) = worker.join().unwrap().unwrap();
+/// assert!(resumed_value <= min_value);
+/// # }
+/// ```
+///
+/// The function only needs to be `Send` when it is moved to another thread, it can otherwise capture a `Cell` or an `Rc`:
+///
+/// ```rust
+/// # use simplers_optimization::Optimizer;
+/// # use std::cell::Cell;
+/// # fn main() {
+/// let nb_calls = Cell::new(0);
+/// let f = |v:&[f64]| { nb_calls.set(nb_calls.get() + 1); v[0] * v[1] };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+///
+/// let (min_value, _) = Optimizer::minimize(&f, &input_interval, 50);
+/// assert!(min_value < 0.);
+/// assert!(nb_calls.get() > 50);
+/// # }
+/// ```
+///
+/// The function can also be stateful (`FnMut`), letting it keep caches, counters or random number generators:
+///
+/// ```rust
+/// # use simplers_optimization::Optimizer;
+/// # fn main() {
+/// let mut nb_calls = 0;
+/// let f = |v:&[f64]| { nb_calls += 1; v[0] * v[1] };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = Optimizer::new(f, &input_interval, should_minimize);
+/// let (min_value, coordinates) = optimizer.nth(50).unwrap();
+/// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+/// let nb_evaluations = optimizer.history().len();
+///
+/// // the optimizer borrows the counter, through the function, until it is dropped
+/// drop(optimizer);
+/// assert_eq!(nb_calls, nb_evaluations);
+/// # }
+/// ```
+pub struct Optimizer<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Scoring = InverseDistanceScoring>
 {
-    exploration_depth: ValueFloat,
-    search_space: SearchSpace<'f_lifetime, CoordFloat, ValueFloat>,
-    best_point: Rc<Point<CoordFloat, ValueFloat>>,
-    min_value: ValueFloat,
-    queue: PriorityQueue<Simplex<CoordFloat, ValueFloat>, OrderedFloat<ValueFloat>>
+    f: Objective<'f_lifetime, CoordFloat, ValueFloat>,
+    /// constraints `g(x) <= 0`, evaluated alongside the function
+    constraints: Vec<Objective<'f_lifetime, CoordFloat, ValueFloat>>,
+    search: AskTellOptimizer<CoordFloat, ValueFloat, Scoring>,
+    stop_condition: Option<StopCondition<ValueFloat>>,
+    /// evolution of the exploration depth during the search, if any
+    exploration_schedule: Option<ExplorationSchedule>,
+    /// the condition that stopped the search, if any
+    stop_reason: Option<StopCondition<ValueFloat>>,
+    /// set once the search has stopped, such that the observers are notified only once
+    is_stopped: bool,
+    progress: Progress,
+    observers: Vec<Box<dyn Observer<CoordFloat, ValueFloat> + 'f_lifetime>>
 }
 
 impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, CoordFloat, ValueFloat>
 {
     /// Creates a new optimizer to explore the given search space with the iterator interface.
     ///
-    /// Takes a function, a vector of intervals describing the input and a boolean describing wether it is a minimization problem (as oppozed to a miximization problem).
+    /// Takes a function (or a reference to a function), a vector of intervals describing the input and a boolean describing wether it is a minimization problem (as oppozed to a miximization problem).
     /// Each cal to the `.next()` function (cf iterator trait) will run an iteration of search and output the best result so far.
     ///
-    /// **Warning:** In d dimenssions, this function will perform d+1 evaluation (call to f) for the initialisation of the search (those should be taken into account when counting iterations).
+    /// **Warning:** In d dimenssions, the first call to `.next()` will perform d+1 additional evaluations (call to f) for the initialisation of the search (those should be taken into account when counting iterations).
     ///
     /// ```rust
     /// # use simplers_optimization::Optimizer;
@@ -46,35 +132,35 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, C
     /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
     /// # }
     /// ```
-    pub fn new(f: &'f_lifetime impl Fn(&[CoordFloat]) -> ValueFloat,
-               input_interval: &[(CoordFloat, CoordFloat)],
+    pub fn new(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+               input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
                should_minimize: bool)
                -> Self
     {
-        // builds initial conditions
-        let search_space = SearchSpace::new(f, input_interval, should_minimize);
-        let initial_simplex = Simplex::initial_simplex(&search_space);
-
-        // various values track through the iterations
-        let best_point = initial_simplex.corners
-                                        .iter()
-                                        .max_by_key(|c| OrderedFloat(c.value))
-                                        .expect("You need at least one dimension!")
-                                        .clone();
-        let min_value = initial_simplex.corners
-                                       .iter()
-                                       .map(|c| c.value)
-                                       .min_by_key(|&v| OrderedFloat(v))
-                                       .expect("You need at least one dimension!");
-
-        // initialize priority queue
-        // no need to evaluate the initial simplex as it will be poped immediatly
-        let mut queue: PriorityQueue<Simplex<CoordFloat, ValueFloat>, OrderedFloat<ValueFloat>> =
-            PriorityQueue::new();
-        queue.push(initial_simplex, OrderedFloat(ValueFloat::zero()));
+        // the corners of the initial simplex are evaluated during the first iteration
+        // which lets the user configure the search beforehand
+        let search = AskTellOptimizer::new(input_interval, should_minimize);
+        Optimizer::from_state(f, search)
+    }
+}
 
-        let exploration_depth = ValueFloat::from(6.).unwrap();
-        Optimizer { exploration_depth, search_space, best_point, min_value, queue }
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Scoring: ScoringStrategy<CoordFloat, ValueFloat>>
+    Optimizer<'f_lifetime, CoordFloat, ValueFloat, Scoring>
+{
+    /// Creates a new optimizer, to explore the given search space with the iterator interface,
+    /// that uses the given strategy to decide which simplex should be explored next.
+    ///
+    /// The default strategy, used by `Optimizer::new`, is the `InverseDistanceScoring`,
+    /// implementing the `ScoringStrategy` trait lets the user experiment with other ways to balance exploration and exploitation
+    /// (see `ScoringStrategy` for an example).
+    pub fn with_scoring_strategy(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                                 input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                                 should_minimize: bool,
+                                 scoring: Scoring)
+                                 -> Self
+    {
+        let search = AskTellOptimizer::with_scoring_strategy(input_interval, should_minimize, scoring);
+        Optimizer::from_state(f, search)
     }
 
     /// Sets the exploration depth for the algorithm, useful when using the iterator interface.
@@ -86,9 +172,8 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, C
     /// - high numbers focus on exploitation (no need to go very high)
     /// - 5 appears to be a good default value
     ///
-    /// **WARNING**: this function should not be used before after an iteration
-    /// (as it will not update the score of already computed points for the next iterations
-    /// which will degrade the quality of the algorithm)
+    /// The depth can be changed at any time during the search (replacing the exploration schedule, if any),
+    /// to change it according to a predefined schedule see `set_exploration_schedule`.
     ///
     /// ```rust
     /// # use simplers_optimization::Optimizer;
@@ -114,10 +199,484 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, C
     /// ```
     pub fn set_exploration_depth(mut self, exploration_depth: usize) -> Self
     {
-        self.exploration_depth = ValueFloat::from(exploration_depth + 1).unwrap();
+        self.search = self.search.set_exploration_depth(exploration_depth);
+        self.exploration_schedule = None;
+        self
+    }
+
+    /// Sets the way the exploration depth evolves during the search (see `ExplorationSchedule`).
+    ///
+    /// The depth is updated before each evaluation, this lets the search explore early and exploit late.
+    /// As all the simplices are scored again when the depth changes, the depth follows the schedule by steps of about 5%.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, ExplorationSchedule, StopCondition};
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// // goes from full exploration to exploitation as the evaluations are consumed
+    /// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                                          .set_exploration_schedule(ExplorationSchedule::Budget { start: 0., end: 20. })
+    ///                                          .set_stop_condition(StopCondition::MaxEvaluations(100))
+    ///                                          .last().unwrap();
+    ///
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn set_exploration_schedule(mut self, exploration_schedule: ExplorationSchedule) -> Self
+    {
+        exploration_schedule.assert_valid();
+        self.exploration_schedule = Some(exploration_schedule);
+        self
+    }
+
+    /// Gives previously evaluated points to the optimizer, letting it start from an informed partition of the search space.
+    ///
+    /// Takes a slice of coordinates associated with their evaluation.
+    /// Each point is inserted by splitting the simplex that contains it (points outside of the search space are ignored).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| (v[0] - 1.).powi(2) + (v[1] - 2.).powi(2);
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    /// // the minimum, 0, was already found by a previous search
+    /// let history = vec![(vec![1., 2.], 0.), (vec![-5., 10.], 100.), (vec![8., -3.], 74.)];
+    ///
+    /// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                                          .with_history(&history)
+    ///                                          .nth(30).unwrap();
+    ///
+    /// // the search cannot find anything better than the historical best
+    /// assert_eq!((min_value, coordinates), (0., vec![1., 2.].into_boxed_slice()));
+    /// # }
+    /// ```
+    pub fn with_history<Coords: AsRef<[CoordFloat]>>(mut self, history: &[(Coords, ValueFloat)]) -> Self
+    {
+        self.search = self.search.with_history(history);
+        self
+    }
+
+    /// Declares that a dimension is only used when a discrete parent dimension takes one of the given values.
+    ///
+    /// When the dimension is inactive, the function receives NaN as its coordinate and the search ignores it.
+    /// Conditions can be chained (a dimension is inactive if its parent is inactive) but cannot form a cycle.
+    ///
+    /// **Warning:** This function should be called before the first iteration
+    /// and will panic if the parent is not discrete or if the conditions form a cycle.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, Dimension, Categories};
+    /// # fn main() {
+    /// #[derive(PartialEq)]
+    /// enum Regularization { None, L2 }
+    /// let regularizations = Categories::new(vec![Regularization::None, Regularization::L2]);
+    ///
+    /// // the strength of the regularization is only meaningful when there is a regularization
+    /// let f = |v:&[f64]| match regularizations.decode(v[1])
+    /// {
+    ///     Regularization::None => (v[0] - 1.).powi(2) + 0.5,
+    ///     Regularization::L2 => (v[0] - 1.).powi(2) + (v[2].log10() + 2.).powi(2)
+    /// };
+    /// let input_interval = vec![Dimension::Continuous(-10., 10.), regularizations.dimension(), Dimension::Log(1e-5, 1.)];
+    /// let should_minimize = true;
+    /// let l2 = regularizations.encode(&Regularization::L2).unwrap();
+    ///
+    /// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                                          .set_condition(2, 1, &[l2])
+    ///                                          .nth(50).unwrap();
+    ///
+    /// println!("min value: {} found in [{}, {}, {}]", min_value, coordinates[0], coordinates[1], coordinates[2]);
+    /// # }
+    /// ```
+    pub fn set_condition(mut self, dimension: usize, parent: usize, active_values: &[CoordFloat]) -> Self
+    {
+        self.search = self.search.set_condition(dimension, parent, active_values);
+        self
+    }
+
+    /// Sets the way evaluations that are not finite (NaN or infinite) are taken into account by the search.
+    ///
+    /// By default, they are replaced by a value slightly worse than the worst value seen so far
+    /// (see `NonFinitePolicy`), their number is given by `optimizer.state().nb_non_finite()`.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, NonFinitePolicy};
+    /// # fn main() {
+    /// // the function is undefined when the product is negative
+    /// let f = |v:&[f64]| (v[0] * v[1]).ln();
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = false;
+    ///
+    /// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                               .set_non_finite_policy(NonFinitePolicy::Substitute { margin: 1. });
+    /// let (max_value, coordinates) = optimizer.nth(50).unwrap();
+    ///
+    /// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
+    /// println!("{} evaluations were not finite", optimizer.state().nb_non_finite());
+    /// # }
+    /// ```
+    pub fn set_non_finite_policy(mut self, non_finite_policy: NonFinitePolicy) -> Self
+    {
+        self.search = self.search.set_non_finite_policy(non_finite_policy);
+        self
+    }
+
+    /// Sets the way the search space is divided into simplices when the search starts (see `Triangulation`).
+    ///
+    /// **Warning:** The `Triangulation::Kuhn` triangulation starts by evaluating the 2^d corners of the hypercube (rather than d+1 points)
+    /// which are all evaluated during the first call to `.next()`.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, Triangulation};
+    /// # fn main() {
+    /// // the minimum is in a corner of the domain
+    /// let f = |v:&[f64]| v[0] + v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                                        .set_triangulation(Triangulation::Kuhn)
+    ///                                        .nth(10)
+    ///                                        .unwrap();
+    /// assert_eq!(min_value, -30.);
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn set_triangulation(mut self, triangulation: Triangulation) -> Self
+    {
+        self.search = self.search.set_triangulation(triangulation);
+        self
+    }
+
+    /// Declares the function as noisy: the best point is evaluated again until it has been evaluated `samples_per_point` times.
+    ///
+    /// The search then uses the mean of the evaluations of each point and returns the point with the best mean
+    /// (rather than a single, potentially lucky, evaluation), see `AskTellOptimizer::set_samples_per_point`.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// // a function with some (deterministic, for the sake of the example) noise
+    /// let mut nb_calls = 0;
+    /// let f = |v:&[f64]| { nb_calls += 1; v[0] * v[1] + if nb_calls % 2 == 0 { 5. } else { -5. } };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = Optimizer::new(f, &input_interval, should_minimize).set_samples_per_point(4);
+    /// let (min_value, coordinates) = optimizer.nth(100).unwrap();
+    ///
+    /// let (variance, nb_samples) = optimizer.state().best_variance().unwrap();
+    /// assert_eq!(nb_samples, 4);
+    /// assert!(variance > 0.);
+    ///
+    /// // the reported value is the mean of the evaluations of the best point
+    /// let samples: Vec<f64> = optimizer.history()
+    ///                                  .iter()
+    ///                                  .filter(|evaluation| evaluation.coordinates == coordinates)
+    ///                                  .map(|evaluation| evaluation.value)
+    ///                                  .collect();
+    /// assert_eq!(samples.len(), nb_samples);
+    /// assert!((min_value - samples.iter().sum::<f64>() / nb_samples as f64).abs() < 1e-9);
+    /// # }
+    /// ```
+    pub fn set_samples_per_point(mut self, samples_per_point: usize) -> Self
+    {
+        self.search = self.search.set_samples_per_point(samples_per_point);
+        self
+    }
+
+    /// Adds a constraint `g(x) <= 0` that is evaluated alongside the function at each iteration.
+    ///
+    /// Points that violate a constraint are infeasible: the search steers away from the regions where they are found
+    /// and they are never returned as the best point (see `AskTellOptimizer::tell_constrained`).
+    /// The function should still return a meaningful value on infeasible points, without any penalty.
+    ///
+    /// **Warning:** `.next()` keeps evaluating points until a feasible point has been found (or the search stops),
+    /// a stop condition (see `set_stop_condition`) insures that the search terminates if there is no feasible point.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// // the points should stay in a disk of radius 5 and above the line x = y
+    /// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                                          .add_constraint(|v:&[f64]| v[0].powi(2) + v[1].powi(2) - 25.)
+    ///                                          .add_constraint(|v:&[f64]| v[0] - v[1])
+    ///                                          .nth(100).unwrap();
+    ///
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// assert!(coordinates[0].powi(2) + coordinates[1].powi(2) <= 25.);
+    /// assert!(coordinates[0] <= coordinates[1]);
+    /// # }
+    /// ```
+    pub fn add_constraint(mut self, g: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime) -> Self
+    {
+        self.constraints.push(Box::new(g));
+        self
+    }
+
+    /// Suggests a point to the optimizer, it will be evaluated during the next iteration
+    /// (several suggestions are evaluated in the order in which they were given).
+    ///
+    /// The simplex that contains the point is split around the point (rather than around its center).
+    /// Coordinates outside of the search space are ignored.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| (v[0] + 3.) * (v[0] + 3.);
+    /// let input_interval = vec![(-10., 10.)];
+    /// let should_minimize = true;
+    ///
+    /// // the first iteration evaluates -10, 10 and the center, 0, of the initial simplex
+    /// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize);
+    /// optimizer.next();
+    ///
+    /// // an expert thinks that the minimum is around this point
+    /// optimizer.suggest(&[-3.]);
+    /// let (min_value, coordinates) = optimizer.next().unwrap();
+    /// assert_eq!(optimizer.history().last().unwrap().coordinates[0], -3.);
+    /// assert_eq!((min_value, coordinates[0]), (0., -3.));
+    ///
+    /// // the segment [-10, 0] has been split into [-10, -3] and [-3, 0] (rather than around its center, -5)
+    /// optimizer.next();
+    /// let coordinates = &optimizer.history().last().unwrap().coordinates;
+    /// assert!((coordinates[0] - -6.5).abs() < 1e-9 || (coordinates[0] - -1.5).abs() < 1e-9);
+    /// # }
+    /// ```
+    pub fn suggest(&mut self, coordinates: &[CoordFloat])
+    {
+        self.search.suggest(coordinates);
+    }
+
+    /// Sets a condition that stops the search, after which `.next()` returns `None`.
+    ///
+    /// The condition is checked before each evaluation (including the evaluations used to initialize the search)
+    /// and the condition that fired is given by `stop_reason`.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, StopCondition};
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize)
+    ///                               .set_stop_condition(StopCondition::MaxEvaluations(100).or(StopCondition::MinRatio(1e-6)));
+    /// let (min_value, coordinates) = optimizer.by_ref().last().unwrap();
+    ///
+    /// assert_eq!(optimizer.stop_reason(), Some(&StopCondition::MaxEvaluations(100)));
+    /// assert_eq!(optimizer.history().len(), 100);
+    /// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+    /// assert!(min_value < -150.);
+    /// # }
+    /// ```
+    pub fn set_stop_condition(mut self, stop_condition: StopCondition<ValueFloat>) -> Self
+    {
+        self.stop_condition = Some(stop_condition);
+        self.stop_reason = None;
+        self.is_stopped = false;
         self
     }
 
+    /// Returns the condition that stopped the search, if any
+    /// (`None` if the search is still running, was stopped by an observer
+    /// or if all the points of a discrete search space have been evaluated).
+    pub fn stop_reason(&self) -> Option<&StopCondition<ValueFloat>>
+    {
+        self.stop_reason.as_ref()
+    }
+
+    /// Returns all the evaluations performed so far, in order, with their coordinates, value, iteration and duration.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize);
+    /// optimizer.nth(30);
+    ///
+    /// // values are given as returned by the function, even when minimizing
+    /// let nb_negative = optimizer.history().iter().filter(|evaluation| evaluation.value < 0.).count();
+    /// println!("{} evaluations out of {} were negative", nb_negative, optimizer.history().len());
+    /// # }
+    /// ```
+    pub fn history(&self) -> &[Evaluation<CoordFloat, ValueFloat>]
+    {
+        self.search.history()
+    }
+
+    /// Registers an observer that will be notified of the events of the search (see `Observer`).
+    ///
+    /// Observers are called in the order in which they were registered.
+    pub fn add_observer(mut self, observer: impl Observer<CoordFloat, ValueFloat> + 'f_lifetime) -> Self
+    {
+        self.observers.push(Box::new(observer));
+        // splits are only recorded when someone is interested
+        self.search.splits.get_or_insert_with(Vec::new);
+        self
+    }
+
+    /// Creates an optimizer that resumes a search from its state.
+    ///
+    /// Takes a function and the state of a search, as produced by `Optimizer::into_state`
+    /// (or saved and loaded with the `Serialize` / `Deserialize` implementations of `AskTellOptimizer` when the `serde` feature is enabled).
+    /// The function should be the one used to build the state as the search does not store it.
+    /// Neither does it store the constraints, stop condition, exploration schedule or observers:
+    /// they need to be registered again on the new optimizer
+    /// (with `add_constraint`, `set_stop_condition`, `set_exploration_schedule` and `add_observer`).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// // runs 30 iterations and stores the state of the search
+    /// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize);
+    /// optimizer.nth(30);
+    /// let state = optimizer.into_state();
+    ///
+    /// // resumes the search for 30 more iterations
+    /// let (min_value, coordinates) = Optimizer::from_state(&f, state).nth(30).unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn from_state(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                      mut state: AskTellOptimizer<CoordFloat, ValueFloat, Scoring>)
+                      -> Self
+    {
+        // splits are only recorded once an observer is registered
+        state.splits = None;
+        Optimizer { f: Box::new(f),
+                    constraints: Vec::new(),
+                    search: state,
+                    stop_condition: None,
+                    exploration_schedule: None,
+                    stop_reason: None,
+                    is_stopped: false,
+                    progress: Progress::new(),
+                    observers: Vec::new() }
+    }
+
+    /// Returns the state of the search, which can be used to resume it later with `Optimizer::from_state`.
+    pub fn state(&self) -> &AskTellOptimizer<CoordFloat, ValueFloat, Scoring>
+    {
+        &self.search
+    }
+
+    /// Consumes the optimizer and returns the state of the search, which can be used to resume it later with `Optimizer::from_state`.
+    pub fn into_state(mut self) -> AskTellOptimizer<CoordFloat, ValueFloat, Scoring>
+    {
+        // the observers are left behind, nobody would collect the splits anymore
+        self.search.splits = None;
+        self.search
+    }
+
+    /// asks for a point, evaluates it and tells its value to the search
+    /// returns None if the stop condition fired or if there is nothing left to evaluate (which can only happen if all dimensions are discrete)
+    fn step(&mut self) -> Option<()>
+    {
+        if self.is_stopped
+        {
+            return None;
+        }
+        self.progress.start();
+        if let Some(stop_reason) = self.stop_condition.as_ref().and_then(|c| c.check(&self.progress, &self.search))
+        {
+            self.stop_reason = Some(stop_reason.clone());
+            return self.stop();
+        }
+
+        if let Some(exploration_schedule) = &self.exploration_schedule
+        {
+            let exploration_depth = exploration_schedule.exploration_depth(&self.progress, self.stop_condition.as_ref());
+            self.search.update_exploration_depth(exploration_depth);
+        }
+        let coordinates = match self.search.ask()
+        {
+            Some(coordinates) => coordinates,
+            None => return self.stop()
+        };
+        let previous_best = self.search.best_point.as_ref().map(|p| p.value);
+        let value = (self.f)(&coordinates);
+        let constraints: Vec<ValueFloat> = self.constraints.iter_mut().map(|g| g(&coordinates)).collect();
+        self.search.tell_constrained(&coordinates, value, &constraints);
+        // the best value can decrease when the best point is evaluated again (see `set_samples_per_point`)
+        let is_improvement = self.search
+                                 .best_point
+                                 .as_ref()
+                                 .is_some_and(|p| previous_best.map_or(true, |previous| p.value > previous));
+        self.progress.record_evaluation(is_improvement);
+
+        if !self.observers.is_empty() && self.notify(is_improvement).is_break()
+        {
+            return self.stop();
+        }
+        Some(())
+    }
+
+    /// notifies the observers of the last evaluation
+    /// returns `ControlFlow::Break` if one of them asked for the search to stop
+    fn notify(&mut self, is_improvement: bool) -> ControlFlow<()>
+    {
+        let splits = self.search.splits.as_mut().map(std::mem::take).unwrap_or_default();
+        let evaluation = self.search.history.last().expect("Impossible: an evaluation was just told!");
+        let best = self.search.best().filter(|_| is_improvement);
+        let mut control_flow = ControlFlow::Continue(());
+        for observer in self.observers.iter_mut()
+        {
+            for (parent, children) in splits.iter()
+            {
+                observer.on_split(parent, children);
+            }
+            if let Some((best_value, best_coordinates)) = &best
+            {
+                observer.on_new_best(*best_value, best_coordinates);
+            }
+            if observer.on_evaluation(evaluation).is_break()
+            {
+                control_flow = ControlFlow::Break(());
+            }
+        }
+        control_flow
+    }
+
+    /// marks the search as stopped and notifies the observers
+    fn stop(&mut self) -> Option<()>
+    {
+        self.is_stopped = true;
+        let stop_reason = self.stop_reason.as_ref();
+        self.observers.iter_mut().for_each(|observer| observer.on_stop(stop_reason));
+        None
+    }
+
+    /// runs the search until the stop condition fires and returns the best point with the condition that fired
+    fn run_until(mut self,
+                 stop_condition: StopCondition<ValueFloat>)
+                 -> (ValueFloat, Coordinates<CoordFloat>, Option<StopCondition<ValueFloat>>)
+    {
+        self.stop_condition = Some(stop_condition);
+        self.by_ref().last();
+        let (best_value, best_coordinates) =
+            self.search.best().expect("The search should be allowed to perform at least one evaluation!");
+        (best_value, best_coordinates, self.stop_reason)
+    }
+}
+
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
     /// Self contained optimization algorithm.
     ///
     /// Takes a function to maximize, a vector of intervals describing the input and a number of iterations.
@@ -133,15 +692,17 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, C
     /// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
     /// # }
     /// ```
-    pub fn maximize(f: &'f_lifetime impl Fn(&[CoordFloat]) -> ValueFloat,
-                    input_interval: &[(CoordFloat, CoordFloat)],
+    pub fn maximize(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
                     nb_iterations: usize)
                     -> (ValueFloat, Coordinates<CoordFloat>)
     {
         let initial_iteration_number = input_interval.len() + 1;
         let should_minimize = false;
-        Optimizer::new(f, input_interval, should_minimize).nth(nb_iterations - initial_iteration_number)
-                                                          .unwrap()
+        // the search stops early if all the points of a discrete search space have been evaluated
+        let mut optimizer = Optimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations - initial_iteration_number);
+        optimizer.search.best().unwrap()
     }
 
     /// Self contained optimization algorithm.
@@ -159,70 +720,97 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, C
     /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
     /// # }
     /// ```
-    pub fn minimize(f: &'f_lifetime impl Fn(&[CoordFloat]) -> ValueFloat,
-                    input_interval: &[(CoordFloat, CoordFloat)],
+    pub fn minimize(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
                     nb_iterations: usize)
                     -> (ValueFloat, Coordinates<CoordFloat>)
     {
         let initial_iteration_number = input_interval.len() + 1;
         let should_minimize = true;
-        Optimizer::new(f, input_interval, should_minimize).nth(nb_iterations - initial_iteration_number)
-                                                          .unwrap()
+        // the search stops early if all the points of a discrete search space have been evaluated
+        let mut optimizer = Optimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations - initial_iteration_number);
+        optimizer.search.best().unwrap()
+    }
+
+    /// Self contained optimization algorithm that runs until the stop condition fires.
+    ///
+    /// Takes a function to maximize, a vector of intervals describing the input and a stop condition.
+    /// Returns the best value, its coordinates and the condition that fired
+    /// (`None` if all the points of a discrete search space were evaluated before the condition fired).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, StopCondition};
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] + v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let stop_condition = StopCondition::TargetValue(29.).or(StopCondition::MaxEvaluations(1000));
+    ///
+    /// let (max_value, coordinates, stop_reason) = Optimizer::maximize_until(&f, &input_interval, stop_condition);
+    /// assert_eq!(stop_reason, Some(StopCondition::TargetValue(29.)));
+    /// assert_eq!(max_value, coordinates[0] + coordinates[1]);
+    /// assert!(max_value >= 29.);
+    /// # }
+    /// ```
+    pub fn maximize_until(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                          input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                          stop_condition: StopCondition<ValueFloat>)
+                          -> (ValueFloat, Coordinates<CoordFloat>, Option<StopCondition<ValueFloat>>)
+    {
+        let should_minimize = false;
+        Optimizer::new(f, input_interval, should_minimize).run_until(stop_condition)
+    }
+
+    /// Self contained optimization algorithm that runs until the stop condition fires.
+    ///
+    /// Takes a function to minimize, a vector of intervals describing the input and a stop condition.
+    /// Returns the best value, its coordinates and the condition that fired
+    /// (`None` if all the points of a discrete search space were evaluated before the condition fired).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, StopCondition};
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let stop_condition = StopCondition::NoImprovement(50).or(StopCondition::MaxEvaluations(1000));
+    ///
+    /// let (min_value, coordinates, stop_reason) = Optimizer::minimize_until(&f, &input_interval, stop_condition);
+    /// assert_eq!(stop_reason, Some(StopCondition::NoImprovement(50)));
+    /// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+    /// assert!(min_value < -150.);
+    /// # }
+    /// ```
+    pub fn minimize_until(f: impl FnMut(&[CoordFloat]) -> ValueFloat + 'f_lifetime,
+                          input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                          stop_condition: StopCondition<ValueFloat>)
+                          -> (ValueFloat, Coordinates<CoordFloat>, Option<StopCondition<ValueFloat>>)
+    {
+        let should_minimize = true;
+        Optimizer::new(f, input_interval, should_minimize).run_until(stop_condition)
     }
 }
 
 /// implements iterator for the Optimizer to give full control on the stopping condition to the user
-impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Iterator
-    for Optimizer<'f_lifetime, CoordFloat, ValueFloat>
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Scoring: ScoringStrategy<CoordFloat, ValueFloat>> Iterator
+    for Optimizer<'f_lifetime, CoordFloat, ValueFloat, Scoring>
 {
     type Item = (ValueFloat, Coordinates<CoordFloat>);
 
     /// runs an iteration of the optimization algorithm and returns the best result so far
+    /// returns None once all the points of a discrete search space have been evaluated
     fn next(&mut self) -> Option<Self::Item>
     {
-        // gets the exploration depth for later use
-        let exploration_depth = self.exploration_depth;
-
-        // gets an up to date simplex
-        let mut simplex = self.queue.pop().expect("Impossible: The queue cannot be empty!").0;
-        let current_difference = self.best_point.value - self.min_value;
-        while simplex.difference != current_difference
+        // evaluates the corners of the initial simplex
+        while !self.search.is_initialized()
         {
-            // updates the simplex and pushes it back into the queue
-            simplex.difference = current_difference;
-            let new_evaluation = simplex.evaluate(exploration_depth);
-            self.queue.push(simplex, OrderedFloat(new_evaluation));
-            // pops a new simplex
-            simplex = self.queue.pop().expect("Impossible: The queue cannot be empty!").0;
+            self.step()?;
         }
-
-        // evaluate the center of the simplex
-        let coordinates = simplex.center.clone();
-        let value = self.search_space.evaluate(&coordinates);
-        let new_point = Rc::new(Point { coordinates, value });
-
-        // splits the simplex around its center and push the subsimplex into the queue
-        simplex.split(new_point.clone(), current_difference)
-               .into_iter()
-               .map(|s| (OrderedFloat(s.evaluate(exploration_depth)), s))
-               .for_each(|(e, s)| {
-                   self.queue.push(s, e);
-               });
-
-        // updates the difference
-        if value > self.best_point.value
-        {
-            self.best_point = new_point;
-        }
-        else if value < self.min_value
+        self.step()?;
+        // there is no best point until a feasible point has been found
+        while self.search.best_point.is_none()
         {
-            self.min_value = value;
+            self.step()?;
         }
-
-        // gets the best value so far
-        let best_value =
-            if self.search_space.minimize { -self.best_point.value } else { self.best_point.value };
-        let best_coordinate = self.search_space.to_hypercube(self.best_point.coordinates.clone());
-        Some((best_value, best_coordinate))
+        self.search.best()
     }
 }
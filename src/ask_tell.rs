@@ -0,0 +1,1818 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::simplex::*;
+use crate::search_space::*;
+use crate::evaluation::*;
+use crate::observer::*;
+use crate::multi_fidelity::Fidelity;
+use crate::scoring::*;
+use priority_queue::PriorityQueue;
+use ordered_float::OrderedFloat;
+use num_traits::Float;
+use std::sync::Arc;
+use std::time::Instant;
+use std::collections::{VecDeque, HashMap, HashSet};
+
+/// Describes how the optimizer deals with points that have been asked but whose value is not known yet.
+///
+/// This matters when several points are asked before being told (see `AskTellOptimizer::ask_batch`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PendingStrategy
+{
+    /// The simplex being evaluated is withheld from the search until its value is told.
+    ///
+    /// This is the default, it is equivalent to a sequential search but means that,
+    /// right after the initialization, a batch might contain less points than requested.
+    Withhold,
+    /// The simplex is split immediately using the worst value seen so far as a placeholder (the "constant liar" strategy).
+    ///
+    /// This pushes the rest of the batch away from the pending points.
+    /// If no finite value is known yet, the placeholder is zero.
+    ConstantLiar,
+    /// The simplex is split immediately using the value interpolated from its corners as a placeholder.
+    Interpolation
+}
+
+/// Describes how the optimizer deals with evaluations that failed (see `AskTellOptimizer::tell_failure`).
+///
+/// ```rust
+/// # use simplers_optimization::FallibleOptimizer;
+/// # fn main() {
+/// // the function is only defined in a corner of the search space,
+/// // such that all the corners of the initial simplex, and its center, fail
+/// let f = |v:&[f64]| if v[0] > 5. && v[1] > 5. { Ok(v[0] + v[1]) } else { Err("undefined") };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// // the search keeps going until it finds a point where the function is defined
+/// let mut optimizer = FallibleOptimizer::new(&f, &input_interval, should_minimize);
+/// let (min_value, coordinates) = optimizer.next().unwrap().unwrap();
+/// assert!(coordinates[0] > 5. && coordinates[1] > 5.);
+/// assert_eq!(min_value, coordinates[0] + coordinates[1]);
+/// assert!(optimizer.failures().len() >= 4);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FailurePolicy
+{
+    /// The point is given the worst value seen so far, pushing the search away from it.
+    ///
+    /// If nothing has been evaluated successfully yet, the point gets a provisional value
+    /// which is replaced by the worst value once one is known.
+    /// This is the default.
+    WorstValue,
+    /// The simplex that would have been split around the point is dropped from the search.
+    Skip,
+    /// The search should stop, the error is returned to the user.
+    ///
+    /// The ask/tell interface drops the evaluation (as with `Skip`) and lets the user decide when to stop asking.
+    Abort
+}
+
+/// Describes how the optimizer deals with evaluations that are not finite (NaN or infinite).
+///
+/// Such values would otherwise become the best point or propagate to the score of every simplex.
+///
+/// ```rust
+/// # use simplers_optimization::Optimizer;
+/// # fn main() {
+/// // all the corners of the initial simplex fall where the function is not defined
+/// let f = |v:&[f64]| v[0].sqrt() + v[1].sqrt();
+/// let input_interval = vec![(-1., 1.), (-1., 1.)];
+/// let nb_iterations = 50;
+///
+/// let (max_value, coordinates) = Optimizer::maximize(&f, &input_interval, nb_iterations);
+/// assert!(max_value.is_finite() && max_value > 1.5);
+/// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
+/// # }
+/// ```
+///
+/// The search also keeps going when the corners of the initial simplex, and its center, are all NaN:
+///
+/// ```rust
+/// # use simplers_optimization::AskTellOptimizer;
+/// # fn main() {
+/// let f = |v:&[f64]| if v[0] > 5. && v[1] > 5. { v[0] + v[1] } else { f64::NAN };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+/// for _ in 0..50
+/// {
+///     let coordinates = optimizer.ask().unwrap();
+///     optimizer.tell(&coordinates, f(&coordinates));
+/// }
+///
+/// // the first evaluations were NaN
+/// assert!(optimizer.history()[..4].iter().all(|evaluation| evaluation.value.is_nan()));
+/// // but the search reached the region where the function is defined
+/// let (min_value, coordinates) = optimizer.best().unwrap();
+/// assert!(coordinates[0] > 5. && coordinates[1] > 5.);
+/// assert_eq!(min_value, coordinates[0] + coordinates[1]);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NonFinitePolicy
+{
+    /// The value is replaced by the worst finite value seen so far minus a margin,
+    /// expressed as a fraction of the difference between the best and worst values seen so far.
+    ///
+    /// This is the default (with a margin of 0.1).
+    /// If no finite value has been seen yet, the evaluation is treated as a failure
+    /// (the point then gets a provisional value until a finite value is known, see `FailurePolicy::WorstValue`).
+    Substitute
+    {
+        /// fraction of the range of values seen so far that is subtracted from the worst value
+        margin: f64
+    },
+    /// The evaluation is treated as a failure (see `FailurePolicy`).
+    Fail
+}
+
+/// an evaluation that has been handed to the user but whose value is not known yet
+pub(crate) enum Pending<CoordFloat: Float, ValueFloat: Float>
+{
+    /// a corner of the initial simplex, stored with its coordinates in the unit simplex
+    Corner(Coordinates<CoordFloat>),
+    /// a simplex that will be split around its center once the center is evaluated
+    Center(Simplex<CoordFloat, ValueFloat>),
+    /// a placeholder point, already used to split its simplex, that will be replaced once evaluated
+    Placeholder(Arc<Point<CoordFloat, ValueFloat>>),
+    /// a point suggested by the user, stored with its coordinates in the unit simplex
+    Suggestion(Coordinates<CoordFloat>),
+    /// a point of the search that is evaluated again to refine the mean of its evaluations (when the function is noisy)
+    Reevaluation(Arc<Point<CoordFloat, ValueFloat>>)
+}
+
+/// a simplex that has been split and the children that replaced it
+pub(crate) type Split<CoordFloat, ValueFloat> = (SimplexInfo<CoordFloat, ValueFloat>, Vec<SimplexInfo<CoordFloat, ValueFloat>>);
+
+/// when a pending evaluation was asked
+pub(crate) struct Asked
+{
+    /// number of points asked before this one
+    pub(crate) iteration: usize,
+    pub(crate) time: Instant
+}
+
+/// Stores the parameters and current state of a search whose evaluations are performed by the user.
+///
+/// Instead of calling a function, the optimizer hands out coordinates with `ask` and receives their evaluation with `tell`
+/// which lets the user evaluate the function whenever and wherever they want (in another process, on a cluster, etc).
+///
+/// - `ValueFloat` is the float type used to represent the evaluations (such as f64)
+/// - `CoordFloat` is the float type used to represent the coordinates (such as f32)
+///
+/// **Warning:** In d dimenssions, the first d+1 calls to `ask` will return the corners of the initial simplex.
+///
+/// ```rust
+/// # use simplers_optimization::AskTellOptimizer;
+/// # fn main() {
+/// let f = |v:&[f64]| v[0] * v[1];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+/// let mut smallest_value = f64::INFINITY;
+/// for _ in 0..100
+/// {
+///     // the evaluation could be done anywhere, at any time
+///     let coordinates = optimizer.ask().unwrap();
+///     let value = f(&coordinates);
+///     optimizer.tell(&coordinates, value);
+///     smallest_value = smallest_value.min(value);
+/// }
+///
+/// // the best point is the smallest value that was told to the optimizer
+/// let (min_value, coordinates) = optimizer.best().unwrap();
+/// assert_eq!(min_value, smallest_value);
+/// assert_eq!(min_value, f(&coordinates));
+/// assert!(min_value < -150.);
+/// # }
+/// ```
+pub struct AskTellOptimizer<CoordFloat: Float, ValueFloat: Float, Scoring = InverseDistanceScoring>
+{
+    pub(crate) exploration_depth: ValueFloat,
+    /// computes the score of the simplices
+    pub(crate) scoring: Scoring,
+    pub(crate) pending_strategy: PendingStrategy,
+    /// number of times the best point is evaluated (more than one when the function is noisy)
+    pub(crate) samples_per_point: usize,
+    /// fraction of the full fidelity values that a low fidelity evaluation should beat to be promoted
+    pub(crate) promotion_quantile: f64,
+    /// sum of the differences between the full and low fidelity values of the promoted points, and their number
+    /// used to estimate full fidelity values from low fidelity values
+    pub(crate) fidelity_offset: ValueFloat,
+    pub(crate) nb_promoted: usize,
+    pub(crate) failure_policy: FailurePolicy,
+    pub(crate) non_finite_policy: NonFinitePolicy,
+    /// number of evaluations that were not finite
+    pub(crate) nb_non_finite: usize,
+    pub(crate) search_space: SearchSpace<CoordFloat>,
+    /// corners of the initial simplex that have not been asked yet
+    pub(crate) initial_coordinates: Vec<Coordinates<CoordFloat>>,
+    /// corners of the initial simplex that have been evaluated, the search starts once they are all known
+    pub(crate) initial_corners: Vec<Arc<Point<CoordFloat, ValueFloat>>>,
+    /// corners of the initial simplex whose evaluation failed, they get the worst value once all corners are known
+    pub(crate) failed_corners: Vec<Coordinates<CoordFloat>>,
+    /// points whose evaluation failed before any value was known, they hold a provisional value of zero
+    /// until the first finite value is found and are then given the worst value seen so far
+    pub(crate) fallback_points: Vec<Arc<Point<CoordFloat, ValueFloat>>>,
+    /// points suggested by the user that have not been asked yet, stored with their coordinates in the hypercube
+    pub(crate) suggestions: VecDeque<Coordinates<CoordFloat>>,
+    /// evaluated points, provided by the user, that will be inserted once the initial simplex has been built
+    pub(crate) postponed: Vec<Arc<Point<CoordFloat, ValueFloat>>>,
+    /// values (and constraint violations) of the evaluated coordinates, indexed by their coordinates in the hypercube
+    /// only used when some dimensions are discrete, to avoid evaluating the same coordinates twice
+    pub(crate) evaluations: HashMap<Vec<u64>, (ValueFloat, ValueFloat)>,
+    /// evaluations that have been asked but not told yet, indexed by their coordinates in the hypercube
+    pub(crate) pending: Vec<(Coordinates<CoordFloat>, Pending<CoordFloat, ValueFloat>, Asked)>,
+    /// all the evaluations told to the optimizer, in the order in which they were told
+    pub(crate) history: Vec<Evaluation<CoordFloat, ValueFloat>>,
+    /// number of points handed to the user so far
+    pub(crate) nb_asked: usize,
+    /// splits performed since they were last collected, with the parent and its children
+    /// only recorded when some observer is interested (`None` otherwise)
+    pub(crate) splits: Option<Vec<Split<CoordFloat, ValueFloat>>>,
+    /// best feasible point so far
+    pub(crate) best_point: Option<Arc<Point<CoordFloat, ValueFloat>>>,
+    /// worst and best values so far, including infeasible points, used to scale the exploration
+    pub(crate) min_value: ValueFloat,
+    pub(crate) max_value: ValueFloat,
+    /// largest finite constraint violation so far, used to compare infeasible regions
+    pub(crate) max_violation: ValueFloat,
+    pub(crate) queue: PriorityQueue<Simplex<CoordFloat, ValueFloat>, Priority<CoordFloat, ValueFloat>>
+}
+
+impl<CoordFloat: Float, ValueFloat: Float> AskTellOptimizer<CoordFloat, ValueFloat>
+{
+    /// Creates a new optimizer to explore the given search space with the ask/tell interface.
+    ///
+    /// Takes a vector of intervals describing the input and a boolean describing wether it is a minimization problem (as oppozed to a miximization problem).
+    pub fn new(input_interval: &[impl Into<Dimension<CoordFloat>> + Clone], should_minimize: bool) -> Self
+    {
+        AskTellOptimizer::with_scoring_strategy(input_interval, should_minimize, InverseDistanceScoring)
+    }
+}
+
+impl<CoordFloat: Float, ValueFloat: Float, Scoring: ScoringStrategy<CoordFloat, ValueFloat>>
+    AskTellOptimizer<CoordFloat, ValueFloat, Scoring>
+{
+    /// Creates a new optimizer, to explore the given search space with the ask/tell interface,
+    /// that uses the given strategy to decide which simplex should be explored next (see `ScoringStrategy`).
+    pub fn with_scoring_strategy(input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                                 should_minimize: bool,
+                                 scoring: Scoring)
+                                 -> Self
+    {
+        let search_space = SearchSpace::new(input_interval, should_minimize);
+        let initial_coordinates = search_space.initial_corners();
+        let exploration_depth = ValueFloat::from(6.).unwrap();
+        AskTellOptimizer { exploration_depth,
+                           scoring,
+                           pending_strategy: PendingStrategy::Withhold,
+                           samples_per_point: 1,
+                           promotion_quantile: 0.5,
+                           fidelity_offset: ValueFloat::zero(),
+                           nb_promoted: 0,
+                           failure_policy: FailurePolicy::WorstValue,
+                           non_finite_policy: NonFinitePolicy::Substitute { margin: 0.1 },
+                           nb_non_finite: 0,
+                           search_space,
+                           initial_coordinates,
+                           initial_corners: Vec::new(),
+                           failed_corners: Vec::new(),
+                           fallback_points: Vec::new(),
+                           suggestions: VecDeque::new(),
+                           postponed: Vec::new(),
+                           history: Vec::new(),
+                           nb_asked: 0,
+                           splits: None,
+                           evaluations: HashMap::new(),
+                           pending: Vec::new(),
+                           best_point: None,
+                           min_value: ValueFloat::infinity(),
+                           max_value: ValueFloat::neg_infinity(),
+                           max_violation: ValueFloat::zero(),
+                           queue: PriorityQueue::new() }
+    }
+
+    /// Sets the exploration depth for the algorithm.
+    ///
+    /// See `Optimizer::set_exploration_depth` for the meaning of the parameter.
+    /// The depth can be changed at any time during the search, the simplices in the queue are then all scored again.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// # let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// // explores for 50 iterations then exploits for 50 iterations
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize).set_exploration_depth(0);
+    /// for i in 0..100
+    /// {
+    ///     if i == 50
+    ///     {
+    ///         optimizer = optimizer.set_exploration_depth(20);
+    ///     }
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     optimizer.tell(&coordinates, f(&coordinates));
+    /// }
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn set_exploration_depth(mut self, exploration_depth: usize) -> Self
+    {
+        self.change_exploration_depth(ValueFloat::from(exploration_depth + 1).unwrap());
+        self
+    }
+
+    /// updates the exploration depth, which can be fractional, as it evolves during the search (see `ExplorationSchedule`)
+    /// as rescoring the queue is linear in its size, the depth only changes once it has moved by more than 5%
+    pub(crate) fn update_exploration_depth(&mut self, exploration_depth: f64)
+    {
+        let exploration_depth = ValueFloat::from(exploration_depth + 1.).unwrap();
+        let relative_step = (exploration_depth / self.exploration_depth - ValueFloat::one()).abs();
+        if relative_step > ValueFloat::from(0.05).unwrap()
+        {
+            self.change_exploration_depth(exploration_depth);
+        }
+    }
+
+    /// sets the exploration depth (plus one) and rescores all the simplices in the queue
+    ///
+    /// this cannot be done lazily, when the simplices are poped, as a higher depth increases their score:
+    /// a simplex whose score is outdated could stay burried in the queue while being the best one
+    fn change_exploration_depth(&mut self, exploration_depth: ValueFloat)
+    {
+        if exploration_depth != self.exploration_depth
+        {
+            self.exploration_depth = exploration_depth;
+            let simplices: Vec<_> = std::mem::take(&mut self.queue).into_iter().map(|(simplex, _)| simplex).collect();
+            self.requeue(simplices);
+        }
+    }
+
+    /// Sets the way the search space is divided into simplices when the search starts (see `Triangulation`).
+    ///
+    /// **Warning:** The `Triangulation::Kuhn` triangulation starts by evaluating the 2^d corners of the hypercube (rather than d+1 points).
+    /// This function should be called before the first call to `ask` (points given with `with_history` are kept)
+    /// and will panic if the Kuhn triangulation is used with `usize::BITS` dimensions or more.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{AskTellOptimizer, Triangulation};
+    /// # fn main() {
+    /// # let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize).set_triangulation(Triangulation::Kuhn);
+    ///
+    /// // the corners of the domain are evaluated first
+    /// let first_coordinates = optimizer.ask().unwrap();
+    /// assert!(first_coordinates[0] == -10. && first_coordinates[1] == -20.);
+    /// optimizer.tell(&first_coordinates, f(&first_coordinates));
+    ///
+    /// // points given beforehand are kept when changing the triangulation
+    /// let history = vec![(vec![5., 10.], -500.)];
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize).with_history(&history)
+    ///                                      .set_triangulation(Triangulation::Kuhn);
+    /// for _ in 0..4
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     optimizer.tell(&coordinates, f(&coordinates));
+    /// }
+    /// assert_eq!(optimizer.best().unwrap(), (-500., vec![5., 10.].into_boxed_slice()));
+    /// # }
+    /// ```
+    pub fn set_triangulation(mut self, triangulation: Triangulation) -> Self
+    {
+        assert_eq!(self.nb_asked, 0, "The triangulation should be set before asking for the first point!");
+        // the points given before the search started are expressed in the coordinates of the previous triangulation
+        let postponed = std::mem::take(&mut self.postponed);
+        let coordinates: Vec<_> = postponed.iter().map(|p| self.search_space.to_hypercube(&p.coordinates)).collect();
+        self.search_space.triangulation = triangulation;
+        self.initial_coordinates = self.search_space.initial_corners();
+        for (old_point, coordinates) in postponed.into_iter().zip(coordinates)
+        {
+            let coordinates = self.search_space.to_simplex(&coordinates);
+            let new_point = Arc::new(Point { coordinates, ..(*old_point).clone() });
+            if self.best_point.as_ref().is_some_and(|best_point| Arc::ptr_eq(best_point, &old_point))
+            {
+                self.best_point = Some(new_point.clone());
+            }
+            self.postponed.push(new_point);
+        }
+        self
+    }
+
+    /// Sets the way points that have been asked, but not told yet, are taken into account by the search.
+    ///
+    /// When asking for several points at once, `PendingStrategy::ConstantLiar` is recommended
+    /// as it lets the search fill the batch with points that are not clustered in a single region.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{AskTellOptimizer, PendingStrategy};
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    /// let batch_size = 8;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize)
+    ///                                      .set_pending_strategy(PendingStrategy::ConstantLiar);
+    /// let mut nb_evaluations = 0;
+    /// for _ in 0..10
+    /// {
+    ///     // the evaluations of a batch could be done in parallel
+    ///     let batch = optimizer.ask_batch(batch_size);
+    ///     for (i, coordinates) in batch.iter().enumerate()
+    ///     {
+    ///         assert!(batch[..i].iter().all(|other| other != coordinates));
+    ///     }
+    ///     let values: Vec<f64> = batch.iter().map(|coordinates| f(coordinates)).collect();
+    ///     // and told in any order
+    ///     for (coordinates, value) in batch.iter().zip(values).rev()
+    ///     {
+    ///         optimizer.tell(coordinates, value);
+    ///         nb_evaluations += 1;
+    ///     }
+    /// }
+    ///
+    /// // all the evaluations were taken into account
+    /// assert_eq!(optimizer.history().len(), nb_evaluations);
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// assert_eq!(min_value, f(&coordinates));
+    /// assert!(min_value < -100.);
+    /// # }
+    /// ```
+    pub fn set_pending_strategy(mut self, pending_strategy: PendingStrategy) -> Self
+    {
+        self.pending_strategy = pending_strategy;
+        self
+    }
+
+    /// Sets the way evaluations that failed are taken into account by the search (see `tell_failure`).
+    pub fn set_failure_policy(mut self, failure_policy: FailurePolicy) -> Self
+    {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Sets the way evaluations that are not finite (NaN or infinite) are taken into account by the search.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{AskTellOptimizer, NonFinitePolicy};
+    /// # fn main() {
+    /// // the function is undefined when the product is negative
+    /// let f = |v:&[f64]| (v[0] * v[1]).sqrt();
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = false;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize)
+    ///                                      .set_non_finite_policy(NonFinitePolicy::Substitute { margin: 0.5 });
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     let value = f(&coordinates);
+    ///     optimizer.tell(&coordinates, value);
+    /// }
+    ///
+    /// let (max_value, coordinates) = optimizer.best().unwrap();
+    /// assert!(max_value.is_finite());
+    /// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
+    /// println!("{} evaluations were not finite", optimizer.nb_non_finite());
+    /// # }
+    /// ```
+    pub fn set_non_finite_policy(mut self, non_finite_policy: NonFinitePolicy) -> Self
+    {
+        self.non_finite_policy = non_finite_policy;
+        self
+    }
+
+    /// Returns the number of evaluations told to the optimizer that were not finite (NaN or infinite).
+    pub fn nb_non_finite(&self) -> usize
+    {
+        self.nb_non_finite
+    }
+
+    /// Declares the function as noisy: the best point is evaluated again until it has been evaluated `samples_per_point` times.
+    ///
+    /// Each point keeps the mean (and variance) of its evaluations, the mean being used by the search
+    /// and to decide which point is the best (rather than a single, potentially lucky, evaluation).
+    /// Once a point has been evaluated again, `ask` might thus return coordinates that were already evaluated.
+    ///
+    /// The default, 1, means that points are evaluated once.
+    /// Evaluations of a point that fail or are not finite are ignored and the constraints are expected to be deterministic.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// // a deterministic function with some (deterministic, for the sake of the example) noise
+    /// let mut nb_calls = 0;
+    /// let mut f = |v:&[f64]| { nb_calls += 1; v[0] * v[1] + if nb_calls % 2 == 0 { 5. } else { -5. } };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize).set_samples_per_point(4);
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     let value = f(&coordinates);
+    ///     optimizer.tell(&coordinates, value);
+    /// }
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// let (variance, nb_samples) = optimizer.best_variance().unwrap();
+    /// assert_eq!(nb_samples, 4);
+    /// assert!(variance > 0.);
+    ///
+    /// // the reported value is the mean of the evaluations of the best point
+    /// let samples: Vec<f64> = optimizer.history()
+    ///                                  .iter()
+    ///                                  .filter(|evaluation| evaluation.coordinates == coordinates)
+    ///                                  .map(|evaluation| evaluation.value)
+    ///                                  .collect();
+    /// assert_eq!(samples.len(), nb_samples);
+    /// assert!((min_value - samples.iter().sum::<f64>() / nb_samples as f64).abs() < 1e-9);
+    /// # }
+    /// ```
+    pub fn set_samples_per_point(mut self, samples_per_point: usize) -> Self
+    {
+        self.samples_per_point = samples_per_point;
+        self
+    }
+
+    /// Gives previously evaluated points to the optimizer, letting it start from an informed partition of the search space.
+    ///
+    /// Takes a slice of coordinates, in the hypercube, associated with their evaluation.
+    /// Each point is inserted by splitting the simplex that contains it
+    /// (points outside of the hypercube or whose value is not finite are ignored).
+    /// The points are inserted once the corners of the initial simplex have been evaluated.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| (v[0] - 1.).powi(2) + (v[1] - 2.).powi(2);
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    /// // the minimum, 0, was already found by a previous search
+    /// let history = vec![(vec![1., 2.], 0.), (vec![-5., 10.], 100.), (vec![8., -3.], 74.)];
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize).with_history(&history);
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     let value = f(&coordinates);
+    ///     optimizer.tell(&coordinates, value);
+    /// }
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// // the search cannot find anything better than the historical best
+    /// assert_eq!((min_value, coordinates), (0., vec![1., 2.].into_boxed_slice()));
+    /// # }
+    /// ```
+    pub fn with_history<Coords: AsRef<[CoordFloat]>>(mut self, history: &[(Coords, ValueFloat)]) -> Self
+    {
+        for (coordinates, value) in history.iter()
+        {
+            let coordinates = coordinates.as_ref();
+            if self.search_space.contains(coordinates) && value.is_finite()
+            {
+                let value = self.search_space.orient(*value);
+                let coordinates = self.search_space.mask_inactive(coordinates.into());
+                self.remember(&coordinates, value, ValueFloat::zero());
+                let coordinates = self.search_space.to_simplex(&coordinates);
+                self.insert_or_postpone(self.new_point(coordinates, value, ValueFloat::zero()));
+            }
+        }
+        self
+    }
+
+    /// Suggests a point to the optimizer, it will be returned by the next call to `ask`
+    /// (several suggestions are asked in the order in which they were given).
+    ///
+    /// Once evaluated, the point is inserted by splitting the simplex that contains it around the point (rather than around its center).
+    /// Coordinates outside of the hypercube are ignored.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| (v[0] + 3.) * (v[0] + 3.);
+    /// let input_interval = vec![(-10., 10.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+    /// // evaluates the corners of the initial simplex, the segment [-10, 10]
+    /// for _ in 0..2
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     optimizer.tell(&coordinates, f(&coordinates));
+    /// }
+    ///
+    /// optimizer.suggest(&[-3.]);
+    /// let coordinates = optimizer.ask().unwrap();
+    /// assert_eq!(coordinates[0], -3.);
+    /// optimizer.tell(&coordinates, f(&coordinates));
+    ///
+    /// // the segment has been split into [-10, -3] and [-3, 10] (rather than around its center, 0)
+    /// let coordinates = optimizer.ask().unwrap();
+    /// assert!((coordinates[0] - -6.5).abs() < 1e-9 || (coordinates[0] - 3.5).abs() < 1e-9);
+    /// optimizer.tell(&coordinates, f(&coordinates));
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// assert_eq!(coordinates[0], -3.);
+    /// assert_eq!(min_value, 0.);
+    /// # }
+    /// ```
+    pub fn suggest(&mut self, coordinates: &[CoordFloat])
+    {
+        if self.search_space.contains(coordinates)
+        {
+            self.suggestions.push_back(self.search_space.mask_inactive(coordinates.into()));
+        }
+    }
+
+    /// Declares that a dimension is only used when a discrete parent dimension takes one of the given values.
+    ///
+    /// When the dimension is inactive, the function receives NaN as its coordinate and the search ignores it.
+    /// Conditions can be chained (a dimension is inactive if its parent is inactive) but cannot form a cycle.
+    ///
+    /// **Warning:** This function should be called before the first call to `ask`
+    /// and will panic if the parent is not discrete or if the conditions form a cycle.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{AskTellOptimizer, Dimension};
+    /// # fn main() {
+    /// // the second dimension is only used when the first one is equal to 1
+    /// let f = |v:&[f64]| if v[0] == 1. { v[1] * v[1] } else { 1. };
+    /// let input_interval = vec![Dimension::Categorical(2), Dimension::Continuous(-10., 10.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer: AskTellOptimizer<f64, f64> =
+    ///     AskTellOptimizer::new(&input_interval, should_minimize).set_condition(1, 0, &[1.]);
+    /// for _ in 0..50
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     assert!((coordinates[0] == 1.) != coordinates[1].is_nan());
+    ///     let value = f(&coordinates);
+    ///     optimizer.tell(&coordinates, value);
+    /// }
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn set_condition(mut self, dimension: usize, parent: usize, active_values: &[CoordFloat]) -> Self
+    {
+        self.search_space.set_condition(dimension, parent, active_values);
+        self
+    }
+
+    /// Returns the coordinates, in the hypercube, of the next point that should be evaluated.
+    ///
+    /// Returns `None` if no point can be proposed until some of the pending evaluations are told to the optimizer
+    /// (this cannot happen if each call to `ask` is followed by the corresponding call to `tell`)
+    /// or if all the points of a discrete search space have been evaluated.
+    pub fn ask(&mut self) -> Option<Coordinates<CoordFloat>>
+    {
+        // the corners of the initial simplex need to be evaluated before the search can start
+        if !self.initial_coordinates.is_empty()
+        {
+            let corner = self.initial_coordinates.remove(0);
+            let coordinates = self.search_space.to_hypercube(&corner);
+            self.push_pending(coordinates.clone(), Pending::Corner(corner));
+            return Some(coordinates);
+        }
+
+        // points suggested by the user are evaluated before the automatic search
+        if let Some(coordinates) = self.suggestions.pop_front()
+        {
+            let corner = self.search_space.to_simplex(&coordinates);
+            self.push_pending(coordinates.clone(), Pending::Suggestion(corner));
+            return Some(coordinates);
+        }
+
+        // evaluates the best point again until its mean is reliable (when the function is noisy)
+        if let Some(point) = self.point_to_resample()
+        {
+            let coordinates = self.search_space.to_hypercube(&point.coordinates);
+            self.push_pending(coordinates.clone(), Pending::Reevaluation(point));
+            return Some(coordinates);
+        }
+
+        // gets an up to date simplex whose center has not been evaluated yet
+        // simplices whose center is being evaluated are deferred until its value is known
+        let mut deferred = Vec::new();
+        let next = loop
+        {
+            let simplex = match self.pop_simplex()
+            {
+                None => break None,
+                Some(simplex) => simplex
+            };
+            let coordinates = self.search_space.to_hypercube(&simplex.center);
+            match self.evaluations.get(&coordinates_key(&coordinates))
+            {
+                None if self.is_pending(&coordinates) => deferred.push(simplex),
+                None => break Some((simplex, coordinates)),
+                Some(&(value, violation)) =>
+                {
+                    // the center maps to discrete coordinates that were already evaluated, we reuse their value
+                    // a simplex that only covers evaluated coordinates is dropped
+                    // and one that only covers evaluated or pending coordinates waits for their values
+                    if self.is_waiting(&simplex)
+                    {
+                        if !self.is_exhausted(&simplex)
+                        {
+                            deferred.push(simplex);
+                        }
+                    }
+                    else
+                    {
+                        let current_difference = self.current_difference();
+                        let new_point = self.new_point(simplex.center.clone(), value, violation);
+                        let parent = self.describe(&simplex);
+                        self.push_simplices(parent, simplex.split(new_point, current_difference));
+                    }
+                }
+            }
+        };
+        for simplex in deferred
+        {
+            let priority = simplex.priority(self.score(&simplex));
+            self.queue.push(simplex, priority);
+        }
+        let (simplex, coordinates) = next?;
+        let current_difference = self.current_difference();
+
+        // the center of the simplex is the next point to evaluate
+        let placeholder_value = match self.pending_strategy
+        {
+            PendingStrategy::Withhold => None,
+            // the worst value is infinite if nothing has been evaluated successfully yet
+            PendingStrategy::ConstantLiar => Some(Some(self.min_value).filter(|v| v.is_finite()).unwrap_or_else(ValueFloat::zero)),
+            PendingStrategy::Interpolation => Some(simplex.interpolate())
+        };
+        let pending = match placeholder_value
+        {
+            None => Pending::Center(simplex),
+            Some(value) =>
+            {
+                // splits the simplex around a placeholder that will be replaced once the true value is known
+                let placeholder = self.new_point(simplex.center.clone(), value, ValueFloat::zero());
+                let parent = self.describe(&simplex);
+                self.push_simplices(parent, simplex.split(placeholder.clone(), current_difference));
+                Pending::Placeholder(placeholder)
+            }
+        };
+        self.push_pending(coordinates.clone(), pending);
+        Some(coordinates)
+    }
+
+    /// Returns up to `batch_size` points to be evaluated, the evaluations can be told in any order.
+    ///
+    /// The batch might contain less points than requested if the search cannot propose more points
+    /// before some pending evaluations are told (see `set_pending_strategy` to reduce this phenomenon).
+    /// In a discrete search space, coordinates that are pending are never asked again.
+    pub fn ask_batch(&mut self, batch_size: usize) -> Vec<Coordinates<CoordFloat>>
+    {
+        (0..batch_size).map_while(|_| self.ask()).collect()
+    }
+
+    /// Gives the evaluation of coordinates that were previously returned by `ask` to the optimizer.
+    ///
+    /// The evaluations can be told in any order.
+    ///
+    /// Values that are not finite are dealt with according to the `NonFinitePolicy` (see `set_non_finite_policy`).
+    ///
+    /// **Warning:** This function will panic if the coordinates were not produced by `ask` (or were already told).
+    pub fn tell(&mut self, coordinates: &[CoordFloat], value: ValueFloat)
+    {
+        self.tell_constrained(coordinates, value, &[])
+    }
+
+    /// Gives the evaluation of coordinates that were previously returned by `ask` to the optimizer,
+    /// with the values of the constraints `g_i(x) <= 0` at those coordinates.
+    ///
+    /// Points that violate a constraint (a constraint whose value is positive or NaN) are infeasible:
+    /// the search steers away from the regions where they are found and they are never reported by `best`.
+    /// The value of an infeasible point should still be meaningful (it is used to guide the search) but it does not need to include any penalty.
+    ///
+    /// **Warning:** This function will panic if the coordinates were not produced by `ask` (or were already told).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// // the points should stay in a disk of radius 5 and above the line x = y
+    /// let g = |v:&[f64]| vec![v[0].powi(2) + v[1].powi(2) - 25., v[0] - v[1]];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     optimizer.tell_constrained(&coordinates, f(&coordinates), &g(&coordinates));
+    /// }
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// assert!(g(&coordinates).iter().all(|&g| g <= 0.));
+    ///
+    /// let nb_infeasible = optimizer.history().iter().filter(|evaluation| !evaluation.is_feasible()).count();
+    /// println!("{} infeasible points were evaluated", nb_infeasible);
+    /// # }
+    /// ```
+    pub fn tell_constrained(&mut self, coordinates: &[CoordFloat], value: ValueFloat, constraints: &[ValueFloat])
+    {
+        let (pending, asked) = self.take_pending(coordinates);
+        self.log(coordinates, value, constraints, Fidelity::Full, &asked);
+
+        // sums the amount by which each constraint is violated
+        let violation = constraints.iter()
+                                   .map(|&g| if g.is_nan() { ValueFloat::infinity() } else { g.max(ValueFloat::zero()) })
+                                   .fold(ValueFloat::zero(), ::std::ops::Add::add);
+        self.tell_value(coordinates, pending, value, violation, Screening::NotScreened);
+    }
+
+    /// Sets the fraction of the full fidelity values that a low fidelity evaluation should beat to be promoted
+    /// to a full fidelity evaluation (see `should_promote`).
+    ///
+    /// The default, 0.5, promotes points whose estimated value is at least as good as the median full fidelity value.
+    pub fn set_promotion_quantile(mut self, promotion_quantile: f64) -> Self
+    {
+        assert!((0. ..=1.).contains(&promotion_quantile), "The promotion quantile should be between 0 and 1!");
+        self.promotion_quantile = promotion_quantile;
+        self
+    }
+
+    /// Returns true if coordinates whose low fidelity evaluation gave the given value are competitive
+    /// and should be evaluated at full fidelity (then told with `tell_promoted`, or with `tell_low_fidelity` otherwise).
+    ///
+    /// The low fidelity value is calibrated using the points that were evaluated at both fidelities
+    /// (adding the average difference between their full and low fidelity values)
+    /// and compared to the full fidelity values seen so far (see `set_promotion_quantile`).
+    /// All points are promoted until a point has been evaluated at both fidelities.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// // the low fidelity evaluation is a cheap but biased approximation of the full fidelity evaluation
+    /// let f_low = |v:&[f64]| v[0] * v[1] + 10.;
+    /// let f_full = |v:&[f64]| v[0] * v[1] + 0.01 * v[0].powi(2);
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     let low_fidelity_value = f_low(&coordinates);
+    ///     if optimizer.should_promote(low_fidelity_value)
+    ///     {
+    ///         optimizer.tell_promoted(&coordinates, low_fidelity_value, f_full(&coordinates));
+    ///     }
+    ///     else
+    ///     {
+    ///         optimizer.tell_low_fidelity(&coordinates, low_fidelity_value);
+    ///     }
+    /// }
+    ///
+    /// // the best point is always evaluated at full fidelity
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn should_promote(&self, low_fidelity_value: ValueFloat) -> bool
+    {
+        if !low_fidelity_value.is_finite()
+        {
+            return false;
+        }
+        let mut full_values: Vec<ValueFloat> =
+            self.history
+                .iter()
+                .filter(|evaluation| evaluation.fidelity == Fidelity::Full && evaluation.value.is_finite())
+                .map(|evaluation| self.search_space.orient(evaluation.value))
+                .collect();
+        if self.nb_promoted == 0 || full_values.is_empty()
+        {
+            return true;
+        }
+        // compares the estimated value with the full fidelity values sorted from worst to best
+        full_values.sort_unstable_by_key(|&value| OrderedFloat(value));
+        let index = (self.promotion_quantile * (full_values.len() - 1) as f64).round() as usize;
+        let estimated_value = self.search_space.orient(low_fidelity_value) + self.mean_fidelity_offset();
+        estimated_value >= full_values[index]
+    }
+
+    /// Gives the low fidelity evaluation of coordinates, previously returned by `ask`, that were not promoted to a full fidelity evaluation.
+    ///
+    /// The point is inserted in the search with a value estimated from its low fidelity value (see `should_promote`)
+    /// but it is never reported by `best`.
+    ///
+    /// **Warning:** This function will panic if the coordinates were not produced by `ask` (or were already told).
+    pub fn tell_low_fidelity(&mut self, coordinates: &[CoordFloat], low_fidelity_value: ValueFloat)
+    {
+        let (pending, asked) = self.take_pending(coordinates);
+        self.log(coordinates, low_fidelity_value, &[], Fidelity::Low, &asked);
+
+        let low_fidelity_value = self.search_space.orient(low_fidelity_value);
+        // the estimation is oriented back as `tell_value` expects a value in the orientation of the user
+        let estimated_value = self.search_space.orient(low_fidelity_value + self.mean_fidelity_offset());
+        self.tell_value(coordinates, pending, estimated_value, ValueFloat::zero(), Screening::Rejected(low_fidelity_value));
+    }
+
+    /// Gives both the low and full fidelity evaluations of coordinates, previously returned by `ask`, that were promoted to a full fidelity evaluation.
+    ///
+    /// The point is dealt with like a point given to `tell`, the low fidelity value being used to calibrate future low fidelity evaluations.
+    ///
+    /// **Warning:** This function will panic if the coordinates were not produced by `ask` (or were already told).
+    pub fn tell_promoted(&mut self, coordinates: &[CoordFloat], low_fidelity_value: ValueFloat, value: ValueFloat)
+    {
+        let (pending, asked) = self.take_pending(coordinates);
+        self.log(coordinates, low_fidelity_value, &[], Fidelity::Low, &asked);
+        self.log(coordinates, value, &[], Fidelity::Full, &asked);
+
+        let low_fidelity_value = self.search_space.orient(low_fidelity_value);
+        if low_fidelity_value.is_finite() && value.is_finite()
+        {
+            self.fidelity_offset = self.fidelity_offset + self.search_space.orient(value) - low_fidelity_value;
+            self.nb_promoted += 1;
+        }
+        self.tell_value(coordinates, pending, value, ValueFloat::zero(), Screening::Promoted(low_fidelity_value));
+    }
+
+    /// returns the average difference between the full and low fidelity values of the promoted points (zero if there are none)
+    fn mean_fidelity_offset(&self) -> ValueFloat
+    {
+        if self.nb_promoted == 0
+        {
+            ValueFloat::zero()
+        }
+        else
+        {
+            self.fidelity_offset / ValueFloat::from(self.nb_promoted).unwrap()
+        }
+    }
+
+    /// adds an evaluation to the history
+    fn log(&mut self,
+           coordinates: &[CoordFloat],
+           value: ValueFloat,
+           constraints: &[ValueFloat],
+           fidelity: Fidelity,
+           asked: &Asked)
+    {
+        self.history.push(Evaluation { coordinates: coordinates.into(),
+                                       value,
+                                       constraints: constraints.into(),
+                                       fidelity,
+                                       iteration: asked.iteration,
+                                       duration: asked.time.elapsed(),
+                                       failed: false });
+    }
+
+    /// adds a failed evaluation to the history, with a NaN value
+    fn log_failure(&mut self, coordinates: &[CoordFloat], asked: &Asked)
+    {
+        self.history.push(Evaluation { coordinates: coordinates.into(),
+                                       value: ValueFloat::nan(),
+                                       constraints: Box::new([]),
+                                       fidelity: Fidelity::Full,
+                                       iteration: asked.iteration,
+                                       duration: asked.time.elapsed(),
+                                       failed: true });
+    }
+
+    /// gives the value (in the orientation of the user) of a pending evaluation to the search
+    /// values that are not finite are dealt with according to the non finite policy
+    fn tell_value(&mut self,
+                  coordinates: &[CoordFloat],
+                  pending: Pending<CoordFloat, ValueFloat>,
+                  value: ValueFloat,
+                  violation: ValueFloat,
+                  screening: Screening<ValueFloat>)
+    {
+        if value.is_finite()
+        {
+            let value = self.search_space.orient(value);
+            self.tell_pending(coordinates, pending, value, violation, screening);
+            return;
+        }
+
+        self.nb_non_finite += 1;
+        // the worst value is infinite if nothing has been evaluated yet
+        let substitute = match self.non_finite_policy
+        {
+            // substituting the evaluation of a point that is evaluated again would bias its mean
+            _ if matches!(pending, Pending::Reevaluation(_)) => None,
+            NonFinitePolicy::Fail => None,
+            NonFinitePolicy::Substitute { margin } if self.min_value.is_finite() =>
+            {
+                let margin = ValueFloat::from(margin).expect("Unable to convert the margin to the value type.");
+                Some(self.min_value - margin * self.current_difference())
+            }
+            NonFinitePolicy::Substitute { .. } => None
+        };
+        match substitute
+        {
+            None => self.fail_pending(pending),
+            Some(substitute) =>
+            {
+                // substitutes are not taken into account when computing the worst value
+                // such that repeated substitutions do not inflate the range of values
+                let min_value = self.min_value;
+                self.tell_pending(coordinates, pending, substitute, violation, screening);
+                self.min_value = min_value;
+            }
+        }
+    }
+
+    /// gives the evaluation (oriented such that it should be maximized) and constraint violation of a pending evaluation
+    fn tell_pending(&mut self,
+                    coordinates: &[CoordFloat],
+                    pending: Pending<CoordFloat, ValueFloat>,
+                    value: ValueFloat,
+                    violation: ValueFloat,
+                    screening: Screening<ValueFloat>)
+    {
+        // estimations are not remembered such that the coordinates can be evaluated at full fidelity later
+        if !matches!(screening, Screening::Rejected(_))
+        {
+            self.remember(coordinates, value, violation);
+        }
+        match pending
+        {
+            Pending::Corner(coordinates) =>
+            {
+                let new_point = self.new_screened_point(coordinates, value, violation, screening);
+                self.update_best(&new_point);
+                self.initial_corners.push(new_point);
+                self.build_initial_simplex();
+            }
+            Pending::Center(simplex) =>
+            {
+                let current_difference = self.current_difference();
+                let coordinates = simplex.center.clone();
+                let new_point = self.new_screened_point(coordinates, value, violation, screening);
+                let parent = self.describe(&simplex);
+                self.push_simplices(parent, simplex.split(new_point.clone(), current_difference));
+                self.update_best(&new_point);
+            }
+            Pending::Suggestion(coordinates) =>
+            {
+                self.insert_or_postpone(self.new_screened_point(coordinates, value, violation, screening));
+            }
+            Pending::Placeholder(placeholder) =>
+            {
+                let coordinates = placeholder.coordinates.clone();
+                let new_point = self.new_screened_point(coordinates, value, violation, screening);
+                self.replace_point(&placeholder, &new_point);
+                self.update_best(&new_point);
+            }
+            // an estimation would bias the mean of the evaluations of the point
+            Pending::Reevaluation(_) if matches!(screening, Screening::Rejected(_)) => (),
+            Pending::Reevaluation(point) =>
+            {
+                // the constraint violation is not updated as the constraints are expected to be deterministic
+                let new_point = Arc::new(point.add_sample(value));
+                self.remember(coordinates, new_point.value, new_point.violation);
+                self.replace_point(&point, &new_point);
+                self.update_best_resampled(&point, &new_point);
+            }
+        }
+    }
+
+    /// Tells the optimizer that the evaluation of coordinates, previously returned by `ask`, failed.
+    ///
+    /// The failure is dealt with according to the `FailurePolicy` (see `set_failure_policy`)
+    /// and never changes the best value found so far.
+    /// It is recorded in the history as an evaluation with a NaN value (see `Evaluation::failed`).
+    /// A corner of the initial simplex always gets the worst value of the other corners
+    /// (if all corners fail, they get the worst value seen once the search finds a point that can be evaluated).
+    ///
+    /// **Warning:** This function will panic if the coordinates were not produced by `ask` (or were already told).
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{AskTellOptimizer, FailurePolicy};
+    /// # fn main() {
+    /// // the simulation diverges when the product is too large
+    /// let f = |v:&[f64]| if v[0] * v[1] > 100. { Err("diverged") } else { Ok(v[0] * v[1]) };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize)
+    ///                                      .set_failure_policy(FailurePolicy::Skip);
+    /// for _ in 0..100
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     match f(&coordinates)
+    ///     {
+    ///         Ok(value) => optimizer.tell(&coordinates, value),
+    ///         Err(_) => optimizer.tell_failure(&coordinates)
+    ///     }
+    /// }
+    ///
+    /// // the failures are part of the history
+    /// let history = optimizer.history();
+    /// assert!(history.iter().enumerate().all(|(i, evaluation)| evaluation.iteration == i));
+    /// assert!(history.iter().filter(|evaluation| evaluation.failed).all(|evaluation| evaluation.value.is_nan()));
+    ///
+    /// let (min_value, coordinates) = optimizer.best().unwrap();
+    /// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn tell_failure(&mut self, coordinates: &[CoordFloat])
+    {
+        let (pending, asked) = self.take_pending(coordinates);
+        self.log_failure(coordinates, &asked);
+        self.fail_pending(pending);
+    }
+
+    /// deals with a pending evaluation that failed according to the failure policy
+    fn fail_pending(&mut self, pending: Pending<CoordFloat, ValueFloat>)
+    {
+        let use_worst_value = self.failure_policy == FailurePolicy::WorstValue;
+        match pending
+        {
+            Pending::Corner(coordinates) =>
+            {
+                self.failed_corners.push(coordinates);
+                self.build_initial_simplex();
+            }
+            Pending::Center(simplex) =>
+            {
+                // otherwise drops the simplex
+                if use_worst_value
+                {
+                    let current_difference = self.current_difference();
+                    let new_point = self.failed_point(simplex.center.clone());
+                    let parent = self.describe(&simplex);
+                    self.push_simplices(parent, simplex.split(new_point, current_difference));
+                }
+            }
+            Pending::Suggestion(coordinates) =>
+            {
+                if use_worst_value
+                {
+                    let new_point = self.failed_point(coordinates);
+                    self.insert_or_postpone(new_point);
+                }
+            }
+            Pending::Placeholder(placeholder) =>
+            {
+                if use_worst_value
+                {
+                    let new_point = self.failed_point(placeholder.coordinates.clone());
+                    self.replace_point(&placeholder, &new_point);
+                }
+                else
+                {
+                    // drops the simplices that were produced by splitting around the placeholder
+                    let children: Vec<_> = self.queue
+                                               .iter()
+                                               .map(|(simplex, _)| simplex)
+                                               .filter(|simplex| {
+                                                   simplex.corners.iter().any(|c| Arc::ptr_eq(c, &placeholder))
+                                               })
+                                               .cloned()
+                                               .collect();
+                    children.iter().for_each(|simplex| {
+                                       self.queue.remove(simplex);
+                                   });
+                }
+            }
+            // the point keeps the mean of its previous evaluations
+            Pending::Reevaluation(_) => ()
+        }
+    }
+
+    /// Returns all the evaluations told to the optimizer, in the order in which they were told.
+    ///
+    /// The values are the ones that were told (even if they were not finite)
+    /// and failed evaluations are included with a NaN value (see `Evaluation::failed`),
+    /// such that every point that was asked and told appears in the history.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::AskTellOptimizer;
+    /// # fn main() {
+    /// let f = |v:&[f64]| v[0] * v[1];
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = true;
+    ///
+    /// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+    /// for _ in 0..20
+    /// {
+    ///     let coordinates = optimizer.ask().unwrap();
+    ///     let value = f(&coordinates);
+    ///     optimizer.tell(&coordinates, value);
+    /// }
+    ///
+    /// for evaluation in optimizer.history()
+    /// {
+    ///     println!("iteration {}: {} found in [{}, {}] after {:?}",
+    ///              evaluation.iteration, evaluation.value, evaluation.coordinates[0], evaluation.coordinates[1], evaluation.duration);
+    /// }
+    /// # }
+    /// ```
+    pub fn history(&self) -> &[Evaluation<CoordFloat, ValueFloat>]
+    {
+        &self.history
+    }
+
+    /// Returns the best value found so far and its coordinates in the hypercube.
+    ///
+    /// When the function is noisy (see `set_samples_per_point`), the value is the mean of the evaluations of the point.
+    ///
+    /// Returns `None` if no feasible evaluation has been told to the optimizer yet.
+    pub fn best(&self) -> Option<(ValueFloat, Coordinates<CoordFloat>)>
+    {
+        self.best_point.as_ref().map(|best_point| {
+                                    let best_value = self.search_space.orient(best_point.value);
+                                    let best_coordinate = self.search_space.to_hypercube(&best_point.coordinates);
+                                    (best_value, best_coordinate)
+                                })
+    }
+
+    /// Returns the sample variance of the evaluations of the best point and their number (see `set_samples_per_point`).
+    ///
+    /// The variance is zero if the best point was evaluated once.
+    pub fn best_variance(&self) -> Option<(ValueFloat, usize)>
+    {
+        self.best_point.as_ref().map(|best_point| (best_point.variance(), best_point.nb_samples))
+    }
+
+    /// returns the best point if it should be evaluated again, and is not already being evaluated
+    fn point_to_resample(&self) -> Option<Arc<Point<CoordFloat, ValueFloat>>>
+    {
+        // the search needs to be initialized as the corners of the initial simplex are not in the queue yet
+        if !self.is_initialized()
+        {
+            return None;
+        }
+        let best_point = self.best_point.as_ref().filter(|p| p.nb_samples < self.samples_per_point)?;
+        let is_pending = self.pending.iter().any(|(_, pending, _)| {
+                                                 matches!(pending, Pending::Reevaluation(p) if Arc::ptr_eq(p, best_point))
+                                             });
+        Some(best_point.clone()).filter(|_| !is_pending)
+    }
+
+    /// removes and returns the pending evaluation associated with the coordinates
+    fn take_pending(&mut self, coordinates: &[CoordFloat]) -> (Pending<CoordFloat, ValueFloat>, Asked)
+    {
+        // compares the bits of the coordinates as inactive dimensions are NaN
+        let key = coordinates_key(coordinates);
+        let index = self.pending
+                        .iter()
+                        .position(|(c, _, _)| coordinates_key(c) == key)
+                        .expect("The coordinates were not produced by `ask` or have already been told!");
+        let (_, pending, asked) = self.pending.swap_remove(index);
+        (pending, asked)
+    }
+
+    /// stores an evaluation that is handed to the user
+    fn push_pending(&mut self, coordinates: Coordinates<CoordFloat>, pending: Pending<CoordFloat, ValueFloat>)
+    {
+        let asked = Asked { iteration: self.nb_asked, time: Instant::now() };
+        self.nb_asked += 1;
+        self.pending.push((coordinates, pending, asked));
+    }
+
+    /// builds the initial simplices once all of their corners are known
+    fn build_initial_simplex(&mut self)
+    {
+        if !self.is_initialized()
+        {
+            return;
+        }
+
+        // the corners that failed get the worst value of the other corners
+        // (asking them again would fail again)
+        for coordinates in std::mem::take(&mut self.failed_corners)
+        {
+            let new_point = self.failed_point(coordinates);
+            self.initial_corners.push(new_point);
+        }
+
+        let corners = std::mem::take(&mut self.initial_corners);
+        match self.search_space.triangulation
+        {
+            Triangulation::Simplex =>
+            {
+                // no need to evaluate the initial simplex as it will be poped immediatly
+                let initial_simplex = Simplex::initial_simplex(corners);
+                let priority = initial_simplex.priority(ValueFloat::zero());
+                self.queue.push(initial_simplex, priority);
+            }
+            Triangulation::Kuhn =>
+            {
+                let simplices = Simplex::kuhn_simplices(corners, self.current_difference());
+                self.push_simplices(None, simplices);
+            }
+        }
+        std::mem::take(&mut self.postponed).into_iter().for_each(|point| self.insert(point));
+    }
+
+    /// pops the best simplex in the queue, making sure that its score is up to date
+    fn pop_simplex(&mut self) -> Option<Simplex<CoordFloat, ValueFloat>>
+    {
+        // gets an up to date simplex
+        let mut simplex = self.queue.pop()?.0;
+        let current_difference = self.current_difference();
+        while simplex.difference != current_difference
+              || simplex.min_value != self.min_value
+              || simplex.max_violation != self.max_violation
+        {
+            // updates the simplex and pushes it back into the queue
+            simplex.difference = current_difference;
+            simplex.min_value = self.min_value;
+            simplex.max_violation = self.max_violation;
+            let new_evaluation = self.score(&simplex);
+            let priority = simplex.priority(new_evaluation);
+            self.queue.push(simplex, priority);
+            // pops a new simplex
+            simplex = self.queue.pop().expect("Impossible: The queue cannot be empty!").0;
+        }
+        Some(simplex)
+    }
+
+    /// returns true if some coordinates, in the hypercube, have been asked but not told yet
+    /// (which only matters when some dimensions are discrete, as other coordinates are never produced twice)
+    fn is_pending(&self, coordinates: &[CoordFloat]) -> bool
+    {
+        self.search_space.is_discrete() && {
+            let key = coordinates_key(coordinates);
+            self.pending.iter().any(|(pending_coordinates, _, _)| coordinates_key(pending_coordinates) == key)
+        }
+    }
+
+    /// stores the value and constraint violation of some coordinates, in the hypercube, if they might be produced again
+    /// (which only happens when some dimensions are discrete)
+    fn remember(&mut self, coordinates: &[CoordFloat], value: ValueFloat, violation: ValueFloat)
+    {
+        if self.search_space.is_discrete()
+        {
+            self.evaluations.insert(coordinates_key(coordinates), (value, violation));
+        }
+    }
+
+    /// returns true if all the discrete coordinates covered by the simplex have already been evaluated
+    /// (always false if some dimensions are continuous)
+    fn is_exhausted(&self, simplex: &Simplex<CoordFloat, ValueFloat>) -> bool
+    {
+        self.only_covers(simplex, self.evaluations.len(), |key| self.evaluations.contains_key(key))
+    }
+
+    /// returns true if all the coordinates covered by the simplex have been evaluated or are being evaluated
+    fn is_waiting(&self, simplex: &Simplex<CoordFloat, ValueFloat>) -> bool
+    {
+        let pending: HashSet<_> = self.pending.iter().map(|(coordinates, _, _)| coordinates_key(coordinates)).collect();
+        self.only_covers(simplex, self.evaluations.len() + pending.len(), |key| {
+                self.evaluations.contains_key(key) || pending.contains(key)
+            })
+    }
+
+    /// returns true if all the coordinates, in the hypercube, covered by the simplex are known
+    /// (the simplex is assumed to cover more than `nb_known` coordinates otherwise)
+    fn only_covers(&self,
+                   simplex: &Simplex<CoordFloat, ValueFloat>,
+                   nb_known: usize,
+                   is_known: impl Fn(&Vec<u64>) -> bool)
+                   -> bool
+    {
+        let coordinates: Vec<Coordinates<CoordFloat>> =
+            simplex.corners
+                   .iter()
+                   .map(|c| &c.coordinates)
+                   .chain(std::iter::once(&simplex.center))
+                   .map(|c| self.search_space.to_hypercube_unmasked(c))
+                   .collect();
+        // the simplex cannot only cover known coordinates if it covers more coordinates than are known
+        match self.search_space.coordinates_between(&coordinates, nb_known)
+        {
+            None => false,
+            Some(covered) =>
+            {
+                covered.into_iter().map(|c| self.search_space.mask_inactive(c)).all(|c| is_known(&coordinates_key(&c)))
+            }
+        }
+    }
+
+    /// evaluates the simplices, produced by splitting the parent, and push them into the queue
+    fn push_simplices(&mut self,
+                      parent: Option<SimplexInfo<CoordFloat, ValueFloat>>,
+                      simplices: Vec<Simplex<CoordFloat, ValueFloat>>)
+    {
+        let min_value = self.min_value;
+        let max_violation = self.max_violation;
+        let simplices: Vec<_> = simplices.into_iter()
+                                         .map(|mut s| {
+                                             s.min_value = min_value;
+                                             s.max_violation = max_violation;
+                                             (self.score(&s), s)
+                                         })
+                                         .collect();
+
+        // records the split if an observer is interested
+        if let Some(parent) = parent
+        {
+            let children = simplices.iter().map(|(score, s)| self.simplex_info(s, *score)).collect();
+            self.splits.get_or_insert_with(Vec::new).push((parent, children));
+        }
+
+        for (score, simplex) in simplices
+        {
+            let priority = simplex.priority(score);
+            self.queue.push(simplex, priority);
+        }
+    }
+
+    /// describes a simplex that is about to be split (returns None if splits are not recorded)
+    fn describe(&self, simplex: &Simplex<CoordFloat, ValueFloat>) -> Option<SimplexInfo<CoordFloat, ValueFloat>>
+    {
+        self.splits.as_ref().map(|_| self.simplex_info(simplex, self.score(simplex)))
+    }
+
+    /// describes a simplex with its score
+    fn simplex_info(&self, simplex: &Simplex<CoordFloat, ValueFloat>, score: ValueFloat) -> SimplexInfo<CoordFloat, ValueFloat>
+    {
+        SimplexInfo { center: self.search_space.to_hypercube(&simplex.center), ratio: simplex.ratio, score }
+    }
+
+    /// inserts an evaluated point in the search or, if the initial simplex has not been built yet,
+    /// stores it to be inserted once it is
+    fn insert_or_postpone(&mut self, new_point: Arc<Point<CoordFloat, ValueFloat>>)
+    {
+        if self.is_initialized()
+        {
+            self.insert(new_point);
+        }
+        else
+        {
+            self.postponed.push(new_point);
+        }
+    }
+
+    /// inserts an evaluated point in the search by splitting the queued simplex that contains it
+    fn insert(&mut self, new_point: Arc<Point<CoordFloat, ValueFloat>>)
+    {
+        // finds the simplex that contains the point (the most robustly if it is on a face)
+        let container = self.queue
+                            .iter()
+                            .filter_map(|(simplex, _)| {
+                                simplex.barycentric_coordinates(&new_point.coordinates).map(|w| (simplex, w))
+                            })
+                            .max_by_key(|(_, weights)| {
+                                OrderedFloat(weights.iter()
+                                                    .copied()
+                                                    .min_by_key(|&w| OrderedFloat(w))
+                                                    .expect("You need at least one dimension!"))
+                            });
+
+        // splits the simplex if the point is inside and does not overlap with one of its corners
+        if let Some((simplex, weights)) = container
+        {
+            let tolerance = Simplex::<CoordFloat, ValueFloat>::weight_tolerance();
+            let is_inside = weights.iter().all(|&w| w >= -tolerance);
+            let is_corner = weights.iter().filter(|&&w| w > tolerance).count() < 2;
+            if is_inside && !is_corner
+            {
+                let simplex = simplex.clone();
+                self.queue.remove(&simplex);
+                let current_difference = self.current_difference();
+                let parent = self.describe(&simplex);
+                let children = simplex.split_at(new_point.clone(), &weights, current_difference);
+                self.push_simplices(parent, children);
+            }
+        }
+
+        self.update_best(&new_point);
+    }
+
+    /// replaces a point (such as a placeholder) with its new evaluation in all the simplices that use it as a corner
+    /// updating the scores of the simplices in the queue
+    fn replace_point(&mut self,
+                     old_point: &Arc<Point<CoordFloat, ValueFloat>>,
+                     new_point: &Arc<Point<CoordFloat, ValueFloat>>)
+    {
+        let exploration_depth = self.exploration_depth;
+        let nb_evaluations = self.history.len();
+        for (simplex, priority) in self.queue.iter_mut()
+        {
+            if simplex.replace_corner(old_point, new_point)
+            {
+                let view = SimplexView::new(simplex, exploration_depth, nb_evaluations);
+                priority.0 = OrderedFloat(self.scoring.score(&view));
+            }
+        }
+        for (_, pending, _) in self.pending.iter_mut()
+        {
+            match pending
+            {
+                Pending::Center(simplex) =>
+                {
+                    simplex.replace_corner(old_point, new_point);
+                }
+                Pending::Reevaluation(point) if Arc::ptr_eq(point, old_point) => *point = new_point.clone(),
+                _ => ()
+            }
+        }
+    }
+
+    /// builds a point, from coordinates in the unit simplex, keeping track of its active dimensions
+    fn new_point(&self,
+                 coordinates: Coordinates<CoordFloat>,
+                 value: ValueFloat,
+                 violation: ValueFloat)
+                 -> Arc<Point<CoordFloat, ValueFloat>>
+    {
+        self.new_screened_point(coordinates, value, violation, Screening::NotScreened)
+    }
+
+    /// builds a point, from coordinates in the unit simplex, whose evaluation failed
+    /// it gets the worst value seen so far or, if there is none yet, a provisional value of zero
+    /// (which is replaced by the worst value once a finite value is found, see `value_fallback_points`)
+    fn failed_point(&mut self, coordinates: Coordinates<CoordFloat>) -> Arc<Point<CoordFloat, ValueFloat>>
+    {
+        let worst_value = Some(self.min_value).filter(|v| v.is_finite());
+        let value = worst_value.unwrap_or_else(ValueFloat::zero);
+        self.remember(&self.search_space.to_hypercube(&coordinates), value, ValueFloat::zero());
+        let new_point = self.new_point(coordinates, value, ValueFloat::zero());
+        if worst_value.is_none()
+        {
+            self.fallback_points.push(new_point.clone());
+        }
+        new_point
+    }
+
+    /// builds a point, from coordinates in the unit simplex, that might have been evaluated at low fidelity
+    fn new_screened_point(&self,
+                          coordinates: Coordinates<CoordFloat>,
+                          value: ValueFloat,
+                          violation: ValueFloat,
+                          screening: Screening<ValueFloat>)
+                          -> Arc<Point<CoordFloat, ValueFloat>>
+    {
+        let active = self.search_space.active_dimensions(&coordinates);
+        Arc::new(Point { coordinates,
+                         value,
+                         nb_samples: 1,
+                         squared_deviations: ValueFloat::zero(),
+                         violation,
+                         screening,
+                         active })
+    }
+
+    /// changes the values of all the points of the search, rescoring all the simplices accordingly
+    ///
+    /// `value_of` takes coordinates in the hypercube and returns their new value (oriented such that it should be maximized),
+    /// points for which it returns None (such as placeholders) keep their value and are not considered when computing the best point
+    pub(crate) fn revalue(&mut self, value_of: impl Fn(&[CoordFloat]) -> Option<ValueFloat>)
+    {
+        // builds the new points, making sure that a point shared between simplices stays shared
+        let mut new_points: HashMap<*const Point<CoordFloat, ValueFloat>, Arc<Point<CoordFloat, ValueFloat>>> =
+            HashMap::new();
+        let mut best_point: Option<Arc<Point<CoordFloat, ValueFloat>>> = None;
+        let mut min_value = ValueFloat::infinity();
+        let mut max_value = ValueFloat::neg_infinity();
+        let search_space = &self.search_space;
+        let mut update = |point: &Arc<Point<CoordFloat, ValueFloat>>| {
+            new_points.entry(Arc::as_ptr(point))
+                      .or_insert_with(|| {
+                          let new_value = value_of(&search_space.to_hypercube(&point.coordinates));
+                          let new_point =
+                              Arc::new(Point { value: new_value.unwrap_or(point.value), ..(**point).clone() });
+                          if new_value.is_some()
+                          {
+                              if new_point.is_feasible()
+                                 && new_point.is_full_fidelity()
+                                 && best_point.as_ref().map_or(true, |best_point| new_point.value > best_point.value)
+                              {
+                                  best_point = Some(new_point.clone());
+                              }
+                              min_value = min_value.min(new_point.value);
+                              max_value = max_value.max(new_point.value);
+                          }
+                          new_point
+                      })
+                      .clone()
+        };
+
+        let mut simplices: Vec<_> = std::mem::take(&mut self.queue).into_iter().map(|(simplex, _)| simplex).collect();
+        for simplex in simplices.iter_mut()
+        {
+            simplex.corners = simplex.corners.iter().map(&mut update).collect();
+        }
+        for (_, pending, _) in self.pending.iter_mut()
+        {
+            match pending
+            {
+                Pending::Center(simplex) => simplex.corners = simplex.corners.iter().map(&mut update).collect(),
+                Pending::Placeholder(point) | Pending::Reevaluation(point) => *point = update(point),
+                Pending::Corner(_) | Pending::Suggestion(_) => ()
+            }
+        }
+        self.initial_corners = self.initial_corners.iter().map(&mut update).collect();
+        self.postponed = self.postponed.iter().map(&mut update).collect();
+        self.fallback_points = self.fallback_points.iter().map(&mut update).collect();
+        for (key, (value, _)) in self.evaluations.iter_mut()
+        {
+            let coordinates: Coordinates<CoordFloat> =
+                key.iter().map(|&bits| CoordFloat::from(f64::from_bits(bits)).unwrap()).collect();
+            *value = value_of(&coordinates).unwrap_or(*value);
+        }
+
+        if max_value.is_finite()
+        {
+            self.best_point = best_point;
+            self.min_value = min_value;
+            self.max_value = max_value;
+        }
+
+        // rescores all the simplices with the new values
+        self.requeue(simplices);
+    }
+
+    /// replaces the queue with the given simplices, scored with the current range of values and exploration depth
+    fn requeue(&mut self, simplices: Vec<Simplex<CoordFloat, ValueFloat>>)
+    {
+        let current_difference = Some(self.max_value - self.min_value).filter(|d| d.is_finite());
+        self.queue = PriorityQueue::with_capacity(simplices.len());
+        for mut simplex in simplices
+        {
+            simplex.difference = current_difference.unwrap_or(simplex.difference);
+            simplex.min_value = self.min_value;
+            simplex.max_violation = self.max_violation;
+            let priority = simplex.priority(self.score(&simplex));
+            self.queue.push(simplex, priority);
+        }
+    }
+
+    /// computes the score of a simplex with the scoring strategy
+    fn score(&self, simplex: &Simplex<CoordFloat, ValueFloat>) -> ValueFloat
+    {
+        self.scoring.score(&SimplexView::new(simplex, self.exploration_depth, self.history.len()))
+    }
+
+    /// returns the ratio of the smallest simplex in the queue (None if the queue is empty)
+    pub(crate) fn smallest_ratio(&self) -> Option<ValueFloat>
+    {
+        self.queue.iter().map(|(simplex, _)| simplex.ratio).min_by_key(|&ratio| OrderedFloat(ratio))
+    }
+
+    /// returns true once the initial simplices have been built
+    pub(crate) fn is_initialized(&self) -> bool
+    {
+        self.initial_coordinates.is_empty() && !self.pending.iter().any(|(_, p, _)| matches!(p, Pending::Corner(_)))
+    }
+
+    /// returns the best value so far minus the worst value so far
+    /// (infeasible points are taken into account as their values are known)
+    fn current_difference(&self) -> ValueFloat
+    {
+        // nothing has been evaluated yet if all the corners of the initial simplex failed
+        if self.max_value.is_finite()
+        {
+            self.max_value - self.min_value
+        }
+        else
+        {
+            ValueFloat::zero()
+        }
+    }
+
+    /// updates the best point once a point has been evaluated again, as the mean of its evaluations might have decreased
+    fn update_best_resampled(&mut self,
+                             old_point: &Arc<Point<CoordFloat, ValueFloat>>,
+                             new_point: &Arc<Point<CoordFloat, ValueFloat>>)
+    {
+        if self.best_point.as_ref().is_some_and(|best_point| Arc::ptr_eq(best_point, old_point))
+        {
+            // the best point might have been overtaken, the new best point is searched among the corners of the simplices
+            // (placeholders are ignored as their value is not known)
+            let is_placeholder = |point: &Arc<Point<CoordFloat, ValueFloat>>| {
+                self.pending
+                    .iter()
+                    .any(|(_, pending, _)| matches!(pending, Pending::Placeholder(p) if Arc::ptr_eq(p, point)))
+            };
+            let pending_simplices = self.pending.iter().filter_map(|(_, pending, _)| match pending
+                                                       {
+                                                           Pending::Center(simplex) => Some(simplex),
+                                                           _ => None
+                                                       });
+            self.best_point = self.queue
+                                  .iter()
+                                  .map(|(simplex, _)| simplex)
+                                  .chain(pending_simplices)
+                                  .flat_map(|simplex| simplex.corners.iter())
+                                  .filter(|point| {
+                                      point.is_feasible() && point.is_full_fidelity() && !is_placeholder(point)
+                                  })
+                                  .max_by_key(|point| OrderedFloat(point.value))
+                                  .cloned();
+        }
+        self.update_best(new_point);
+    }
+
+    /// updates the best point and the range of values so far with a newly evaluated point
+    fn update_best(&mut self, new_point: &Arc<Point<CoordFloat, ValueFloat>>)
+    {
+        if new_point.is_feasible()
+           && new_point.is_full_fidelity()
+           && self.best_point.as_ref().map_or(true, |best_point| new_point.value > best_point.value)
+        {
+            self.best_point = Some(new_point.clone());
+        }
+        if new_point.value < self.min_value
+        {
+            self.min_value = new_point.value;
+        }
+        if new_point.value > self.max_value
+        {
+            self.max_value = new_point.value;
+        }
+        if new_point.violation.is_finite() && new_point.violation > self.max_violation
+        {
+            self.max_violation = new_point.violation;
+        }
+        if !self.fallback_points.is_empty() && self.min_value.is_finite()
+        {
+            self.value_fallback_points();
+        }
+    }
+
+    /// gives the worst value seen so far to the points whose evaluation failed before any value was known
+    fn value_fallback_points(&mut self)
+    {
+        let worst_value = self.min_value;
+        for old_point in std::mem::take(&mut self.fallback_points)
+        {
+            let new_point = Arc::new(Point { value: worst_value, ..(*old_point).clone() });
+            self.remember(&self.search_space.to_hypercube(&new_point.coordinates), worst_value, ValueFloat::zero());
+            self.replace_point(&old_point, &new_point);
+            // the point might be waiting for the initial simplex to be built
+            for point in self.postponed.iter_mut().filter(|point| Arc::ptr_eq(point, &old_point))
+            {
+                *point = new_point.clone();
+            }
+        }
+    }
+}
+
+/// returns a hashable representation of coordinates
+pub(crate) fn coordinates_key<CoordFloat: Float>(coordinates: &[CoordFloat]) -> Vec<u64>
+{
+    // TODO I will drop `.to_f64().unwrap()`
+    // once the relevant [issue](https://github.com/rust-num/num-traits/issues/123) is resolved
+    coordinates.iter().map(|&x| x.to_f64().unwrap().to_bits()).collect()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    /// changing the exploration depth in the middle of a search should not leave outdated scores in the queue
+    #[test]
+    fn test_pop_after_depth_change()
+    {
+        let f = |v: &[f64]| -(v[0] - 1.).powi(2) - (v[1] + 3.).powi(2) + (3. * v[0]).sin();
+        let input_interval = vec![(-10., 10.), (-20., 20.)];
+        for nb_evaluations in 20..60
+        {
+            let mut optimizer = AskTellOptimizer::new(&input_interval, false).set_exploration_depth(0);
+            for _ in 0..nb_evaluations
+            {
+                let coordinates = optimizer.ask().unwrap();
+                optimizer.tell(&coordinates, f(&coordinates));
+            }
+            optimizer = optimizer.set_exploration_depth(20);
+
+            // scores all the simplices from scratch
+            let fresh_score = |optimizer: &AskTellOptimizer<f64, f64>, simplex: &Simplex<f64, f64>| {
+                let mut simplex = simplex.clone();
+                simplex.difference = optimizer.current_difference();
+                simplex.min_value = optimizer.min_value;
+                simplex.max_violation = optimizer.max_violation;
+                optimizer.score(&simplex)
+            };
+            let best_score = optimizer.queue
+                                      .iter()
+                                      .map(|(simplex, _)| fresh_score(&optimizer, simplex))
+                                      .fold(f64::NEG_INFINITY, f64::max);
+
+            let simplex = optimizer.pop_simplex().unwrap();
+            assert_eq!(fresh_score(&optimizer, &simplex), best_score, "after {} evaluations", nb_evaluations);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use crate::algorithm::Optimizer;
+use num_traits::Float;
+use futures::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Optimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    /// Self contained optimization algorithm for asynchronous functions.
+    ///
+    /// Takes a function to maximize, a vector of intervals describing the input, a number of iterations
+    /// and the maximum number of evaluations that can be in flight at the same time (at least one).
+    /// Each time an evaluation completes, its result is given to the search and a new evaluation is started.
+    ///
+    /// The resulting future is executor agnostic.
+    ///
+    /// **Note:** This function requires the `async` feature.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # use std::cell::Cell;
+    /// # fn main() {
+    /// let nb_evaluations = Cell::new(0);
+    /// let f = |v:&[f64]|
+    /// {
+    ///     nb_evaluations.set(nb_evaluations.get() + 1);
+    ///     let (x, y) = (v[0], v[1]);
+    ///     async move { x + y }
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    /// let max_in_flight = 8;
+    ///
+    /// let search = Optimizer::maximize_async(&f, &input_interval, nb_iterations, max_in_flight);
+    /// let (max_value, coordinates) = futures::executor::block_on(search);
+    /// assert_eq!(nb_evaluations.get(), nb_iterations);
+    /// assert_eq!(max_value, coordinates[0] + coordinates[1]);
+    /// assert!(max_value > 25.);
+    /// # }
+    /// ```
+    pub async fn maximize_async<Fut: Future<Output = ValueFloat>>(f: impl FnMut(&[CoordFloat]) -> Fut,
+                                                                  input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                                                                  nb_iterations: usize,
+                                                                  max_in_flight: usize)
+                                                                  -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let should_minimize = false;
+        optimize_async(f, input_interval, should_minimize, nb_iterations, max_in_flight).await
+    }
+
+    /// Self contained optimization algorithm for asynchronous functions.
+    ///
+    /// Takes a function to minimize, a vector of intervals describing the input, a number of iterations
+    /// and the maximum number of evaluations that can be in flight at the same time (at least one).
+    /// Each time an evaluation completes, its result is given to the search and a new evaluation is started.
+    ///
+    /// The resulting future is executor agnostic.
+    ///
+    /// **Note:** This function requires the `async` feature.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # use std::cell::Cell;
+    /// # fn main() {
+    /// let nb_evaluations = Cell::new(0);
+    /// let f = |v:&[f64]|
+    /// {
+    ///     nb_evaluations.set(nb_evaluations.get() + 1);
+    ///     let (x, y) = (v[0], v[1]);
+    ///     async move { x * y }
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    /// let max_in_flight = 8;
+    ///
+    /// let search = Optimizer::minimize_async(&f, &input_interval, nb_iterations, max_in_flight);
+    /// let (min_value, coordinates) = futures::executor::block_on(search);
+    /// assert_eq!(nb_evaluations.get(), nb_iterations);
+    /// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+    /// assert!(min_value < -100.);
+    /// # }
+    /// ```
+    pub async fn minimize_async<Fut: Future<Output = ValueFloat>>(f: impl FnMut(&[CoordFloat]) -> Fut,
+                                                                  input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                                                                  nb_iterations: usize,
+                                                                  max_in_flight: usize)
+                                                                  -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let should_minimize = true;
+        optimize_async(f, input_interval, should_minimize, nb_iterations, max_in_flight).await
+    }
+}
+
+/// runs the search, keeping up to `max_in_flight` evaluations running, until `nb_iterations` evaluations have been done
+async fn optimize_async<CoordFloat: Float, ValueFloat: Float, Fut: Future<Output = ValueFloat>>(
+    mut f: impl FnMut(&[CoordFloat]) -> Fut,
+    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+    should_minimize: bool,
+    nb_iterations: usize,
+    max_in_flight: usize)
+    -> (ValueFloat, Coordinates<CoordFloat>)
+{
+    assert!(max_in_flight > 0, "You need at least one evaluation in flight!");
+    // the constant liar strategy insures that the evaluations in flight are spread over the search space
+    let mut search = AskTellOptimizer::new(input_interval, should_minimize)
+                                      .set_pending_strategy(PendingStrategy::ConstantLiar);
+    let mut in_flight = FuturesUnordered::new();
+
+    let mut nb_started = 0;
+    loop
+    {
+        // starts new evaluations until the limit is reached
+        while (in_flight.len() < max_in_flight) && (nb_started < nb_iterations)
+        {
+            match search.ask()
+            {
+                None => break,
+                Some(coordinates) =>
+                {
+                    let evaluation = f(&coordinates);
+                    in_flight.push(async move { (coordinates, evaluation.await) });
+                    nb_started += 1;
+                }
+            }
+        }
+
+        // waits for an evaluation to complete and gives it to the search
+        match in_flight.next().await
+        {
+            None => break,
+            Some((coordinates, value)) => search.tell(&coordinates, value)
+        }
+    }
+
+    search.best().expect("You need at least one iteration!")
+}
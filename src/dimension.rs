@@ -0,0 +1,368 @@
+use num_traits::Float;
+
+/// Describes a dimension of the search space.
+///
+/// A tuple `(inf, sup)` can be used instead of `Dimension::Continuous(inf, sup)` when all dimensions are continuous.
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, Dimension};
+/// # fn main() {
+/// // the number of layers is an integer between 1 and 8
+/// // the learning rate spans several orders of magnitude
+/// let f = |v:&[f64]| (v[0] - 3.3).powi(2) + (v[1] - 5.).abs() + (v[2].log10() + 3.).powi(2);
+/// let input_interval = vec![Dimension::Continuous(-10., 10.), Dimension::Integer(1, 8), Dimension::Log(1e-6, 1.)];
+/// let nb_iterations = 50;
+///
+/// let (min_value, coordinates) = Optimizer::minimize(&f, &input_interval, nb_iterations);
+/// println!("min value: {} found in [{}, {}, {}]", min_value, coordinates[0], coordinates[1], coordinates[2]);
+/// # }
+/// ```
+///
+/// A search space made only of discrete dimensions has a finite number of points,
+/// each of them is evaluated at most once and the search stops once they have all been evaluated:
+///
+/// ```rust
+/// # use simplers_optimization::{AskTellOptimizer, Dimension};
+/// # use std::collections::HashSet;
+/// # fn main() {
+/// let f = |v:&[f64]| (v[0] - 2.).powi(2) + (v[1] + 1.).abs();
+/// let input_interval = vec![Dimension::Integer(0, 4), Dimension::Integer(-3, 3)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+/// let mut evaluated = HashSet::new();
+/// while let Some(coordinates) = optimizer.ask()
+/// {
+///     // the same integer coordinates are never asked twice
+///     assert!(evaluated.insert((coordinates[0] as i64, coordinates[1] as i64)));
+///     optimizer.tell(&coordinates, f(&coordinates));
+/// }
+///
+/// // the search ends (returning `None`) once the 5*7 points have been evaluated
+/// assert_eq!(evaluated.len(), 5 * 7);
+/// assert_eq!(optimizer.best().unwrap().0, 0.);
+///
+/// // points that are still being evaluated are not asked again either
+/// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+/// let mut evaluated = HashSet::new();
+/// loop
+/// {
+///     let batch = optimizer.ask_batch(8);
+///     if batch.is_empty()
+///     {
+///         break;
+///     }
+///     for coordinates in &batch
+///     {
+///         assert!(evaluated.insert((coordinates[0] as i64, coordinates[1] as i64)));
+///     }
+///     for coordinates in &batch
+///     {
+///         optimizer.tell(coordinates, f(coordinates));
+///     }
+/// }
+/// assert_eq!(evaluated.len(), 5 * 7);
+/// # }
+/// ```
+///
+/// The bounds are checked when the search space is built:
+///
+/// ```rust,should_panic
+/// # use simplers_optimization::{AskTellOptimizer, Dimension};
+/// # fn main() {
+/// // a logarithmic scale cannot reach zero (whichever bound comes first)
+/// let input_interval = vec![Dimension::Log(1., -1.)];
+/// let optimizer: AskTellOptimizer<f64, f64> = AskTellOptimizer::new(&input_interval, true);
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dimension<CoordFloat: Float>
+{
+    /// All the values between the two bounds.
+    Continuous(CoordFloat, CoordFloat),
+    /// All the values between the two (strictly positive) bounds, explored on a logarithmic scale.
+    ///
+    /// This is useful for parameters spanning several orders of magnitude such as learning rates.
+    /// Building a search space with bounds that are not strictly positive will panic.
+    Log(CoordFloat, CoordFloat),
+    /// All the values between the two bounds (which should be strictly between 0 and 1), explored on a logit scale.
+    ///
+    /// This is useful for parameters that get more sensitive as they approach 0 or 1 such as momentums.
+    /// Building a search space with bounds that are not strictly between 0 and 1 (or with `inf >= sup`) will panic.
+    Logit(CoordFloat, CoordFloat),
+    /// All the values between the two bounds, explored on the scale defined by a monotone bijection.
+    ///
+    /// The search is uniform between `warp(inf)` and `warp(sup)`, `unwarp` being the inverse of `warp`.
+    /// This dimension cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Warped
+    {
+        /// smallest value of the dimension
+        inf: CoordFloat,
+        /// largest value of the dimension
+        sup: CoordFloat,
+        /// monotone function from the user's values to the scale on which the search is done
+        warp: fn(CoordFloat) -> CoordFloat,
+        /// inverse of `warp`
+        unwarp: fn(CoordFloat) -> CoordFloat
+    },
+    /// All the integers between the two bounds (included).
+    ///
+    /// The function will only be evaluated on integer coordinates and the search avoids evaluating the same coordinates twice.
+    Integer(i64, i64),
+    /// A number of unordered categories (at least one), see `Categories` to build it and decode its values.
+    ///
+    /// The function receives the index of the category (0 for the first category, 1 for the second, etc),
+    /// use `Categories::wrap` for a function that takes the category itself.
+    Categorical(usize)
+}
+
+impl<CoordFloat: Float> Dimension<CoordFloat>
+{
+    /// returns true if the dimension only contains a finite number of values
+    pub fn is_discrete(&self) -> bool
+    {
+        matches!(self, Dimension::Integer(_, _) | Dimension::Categorical(_))
+    }
+
+    /// returns true if the value belongs to the dimension
+    pub fn contains(&self, x: CoordFloat) -> bool
+    {
+        let (inf, sup) = self.bounds();
+        (inf <= x) && (x <= sup) && (!self.is_discrete() || (x.round() == x))
+    }
+
+    /// converts a value from the unit interval to the dimension
+    pub fn from_unit(&self, x: CoordFloat) -> CoordFloat
+    {
+        let (inf, sup) = self.bounds();
+        if self.is_discrete()
+        {
+            // each integer gets an interval of the same width
+            let half = CoordFloat::from(0.5).unwrap();
+            let width = sup - inf + CoordFloat::one();
+            let value = (inf - half + x * width).round().max(inf).min(sup);
+            // adding zero turns -0 into 0 such that each integer has a single representation
+            value + CoordFloat::zero()
+        }
+        else
+        {
+            // the search is linear in the warped space
+            let (warped_inf, warped_sup) = (self.warp(inf), self.warp(sup));
+            let value = self.unwarp(warped_inf + x * (warped_sup - warped_inf));
+            // insures that rounding errors do not push the value out of the bounds
+            value.max(inf).min(sup)
+        }
+    }
+
+    /// converts a value from the dimension to the unit interval (this is the inverse of `from_unit`)
+    pub fn to_unit(&self, x: CoordFloat) -> CoordFloat
+    {
+        let (inf, sup) = self.bounds();
+        if self.is_discrete()
+        {
+            let half = CoordFloat::from(0.5).unwrap();
+            let width = sup - inf + CoordFloat::one();
+            (x - inf + half) / width
+        }
+        else
+        {
+            let (warped_inf, warped_sup) = (self.warp(inf), self.warp(sup));
+            (self.warp(x) - warped_inf) / (warped_sup - warped_inf)
+        }
+    }
+
+    /// returns an error message if the bounds of the dimension are outside of the domain of its scale
+    /// (which would otherwise produce NaN coordinates)
+    pub(crate) fn check(&self) -> Result<(), &'static str>
+    {
+        let zero = CoordFloat::zero();
+        let one = CoordFloat::one();
+        match *self
+        {
+            Dimension::Log(inf, sup) if inf > zero && sup > zero => Ok(()),
+            Dimension::Log(_, _) => Err("The bounds of a Log dimension should be strictly positive!"),
+            Dimension::Logit(inf, sup) if zero < inf && inf < sup && sup < one => Ok(()),
+            Dimension::Logit(_, _) => Err("The bounds of a Logit dimension should satisfy 0 < inf < sup < 1!"),
+            _ => Ok(())
+        }
+    }
+
+    /// panics if the bounds of the dimension are outside of the domain of its scale (see `check`)
+    pub(crate) fn assert_valid(&self)
+    {
+        if let Err(message) = self.check()
+        {
+            panic!("{}", message);
+        }
+    }
+
+    /// converts a value of a continuous dimension into the scale on which the search is linear
+    fn warp(&self, x: CoordFloat) -> CoordFloat
+    {
+        match *self
+        {
+            Dimension::Log(_, _) => x.ln(),
+            Dimension::Logit(_, _) => (x / (CoordFloat::one() - x)).ln(),
+            Dimension::Warped { warp, .. } => warp(x),
+            _ => x
+        }
+    }
+
+    /// inverse of `warp`
+    fn unwarp(&self, x: CoordFloat) -> CoordFloat
+    {
+        match *self
+        {
+            Dimension::Log(_, _) => x.exp(),
+            Dimension::Logit(_, _) => CoordFloat::one() / (CoordFloat::one() + (-x).exp()),
+            Dimension::Warped { unwarp, .. } => unwarp(x),
+            _ => x
+        }
+    }
+
+    /// returns all the values of a discrete dimension between the two given values (included)
+    /// returns None if the dimension is continuous
+    pub fn values_between(&self, x: CoordFloat, y: CoordFloat) -> Option<Vec<CoordFloat>>
+    {
+        if !self.is_discrete()
+        {
+            return None;
+        }
+        let (start, end) = (x.min(y).round(), x.max(y).round());
+        let nb_values = (end - start).to_usize()? + 1;
+        Some((0..nb_values).map(|i| start + CoordFloat::from(i).unwrap()).collect())
+    }
+
+    /// returns the smallest and largest values of the dimension
+    fn bounds(&self) -> (CoordFloat, CoordFloat)
+    {
+        let (inf, sup) = match *self
+        {
+            Dimension::Continuous(inf, sup)
+            | Dimension::Log(inf, sup)
+            | Dimension::Logit(inf, sup)
+            | Dimension::Warped { inf, sup, .. } => (inf, sup),
+            Dimension::Integer(inf, sup) =>
+            {
+                (CoordFloat::from(inf).expect("Unable to convert from i64 to coordinate type."),
+                 CoordFloat::from(sup).expect("Unable to convert from i64 to coordinate type."))
+            }
+            Dimension::Categorical(nb_categories) =>
+            {
+                let last_index = nb_categories.checked_sub(1).expect("You need at least one category!");
+                (CoordFloat::zero(),
+                 CoordFloat::from(last_index).expect("Unable to convert from usize to coordinate type."))
+            }
+        };
+        (inf.min(sup), inf.max(sup))
+    }
+}
+
+/// a tuple `(inf, sup)` is a continuous dimension
+impl<CoordFloat: Float> From<(CoordFloat, CoordFloat)> for Dimension<CoordFloat>
+{
+    fn from((inf, sup): (CoordFloat, CoordFloat)) -> Self
+    {
+        Dimension::Continuous(inf, sup)
+    }
+}
+
+/// A list of categories that can be used as a dimension of the search space.
+///
+/// The function receives the index of the category which can be decoded into the category itself
+/// (or it can receive the category directly, see `Categories::wrap`).
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, Dimension, Categories};
+/// # fn main() {
+/// #[derive(PartialEq)]
+/// enum Activation { Relu, Tanh, Sigmoid }
+/// let activations = Categories::new(vec![Activation::Relu, Activation::Tanh, Activation::Sigmoid]);
+///
+/// let f = |v:&[f64]| match activations.decode(v[1])
+/// {
+///     Activation::Relu => v[0] * 2.,
+///     Activation::Tanh => v[0].tanh(),
+///     Activation::Sigmoid => 1. / (1. + (-v[0]).exp())
+/// };
+/// let input_interval = vec![Dimension::Continuous(-10., 10.), activations.dimension()];
+/// let nb_iterations = 50;
+///
+/// let (max_value, coordinates) = Optimizer::maximize(&f, &input_interval, nb_iterations);
+/// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Categories<T>
+{
+    categories: Vec<T>
+}
+
+impl<T> Categories<T>
+{
+    /// Builds a list of categories, there should be at least one category.
+    pub fn new(categories: Vec<T>) -> Self
+    {
+        assert!(!categories.is_empty(), "You need at least one category!");
+        Categories { categories }
+    }
+
+    /// Returns the dimension that should be used, in the search space, to represent the categories.
+    pub fn dimension<CoordFloat: Float>(&self) -> Dimension<CoordFloat>
+    {
+        Dimension::Categorical(self.categories.len())
+    }
+
+    /// Converts the coordinate received by the function into the corresponding category.
+    pub fn decode<CoordFloat: Float>(&self, x: CoordFloat) -> &T
+    {
+        let index = x.round().to_usize().unwrap_or(0).min(self.categories.len() - 1);
+        &self.categories[index]
+    }
+
+    /// Wraps a function that takes the category of the given dimension as a typed value,
+    /// alongside the coordinates, into a function that can be optimized.
+    ///
+    /// Wrappers can be nested when there are several categorical dimensions.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, Dimension, Categories};
+    /// # fn main() {
+    /// #[derive(Debug, PartialEq)]
+    /// enum Activation { Relu, Tanh, Sigmoid }
+    /// let activations = Categories::new(vec![Activation::Relu, Activation::Tanh, Activation::Sigmoid]);
+    ///
+    /// let f = activations.wrap(1, |v:&[f64], activation:&Activation| match activation
+    /// {
+    ///     Activation::Relu => v[0] * 2.,
+    ///     Activation::Tanh => v[0].tanh(),
+    ///     Activation::Sigmoid => 1. / (1. + (-v[0]).exp())
+    /// });
+    /// let input_interval = vec![Dimension::Continuous(-10., 10.), activations.dimension()];
+    /// let nb_iterations = 50;
+    ///
+    /// let (max_value, coordinates) = Optimizer::maximize(f, &input_interval, nb_iterations);
+    /// assert_eq!(activations.decode(coordinates[1]), &Activation::Relu);
+    /// println!("max value: {} found with {:?}", max_value, activations.decode(coordinates[1]));
+    /// # }
+    /// ```
+    pub fn wrap<'a, CoordFloat: Float, Output>(&'a self,
+                                               dimension: usize,
+                                               mut f: impl FnMut(&[CoordFloat], &T) -> Output + 'a)
+                                               -> impl FnMut(&[CoordFloat]) -> Output + 'a
+    {
+        move |coordinates: &[CoordFloat]| f(coordinates, self.decode(coordinates[dimension]))
+    }
+
+    /// Converts a category into the corresponding coordinate
+    /// (useful to suggest a point or to give a history of evaluations to the optimizer).
+    ///
+    /// Returns `None` if the category is not part of the list.
+    pub fn encode<CoordFloat: Float>(&self, category: &T) -> Option<CoordFloat>
+        where T: PartialEq
+    {
+        self.categories.iter().position(|c| c == category).and_then(CoordFloat::from)
+    }
+}
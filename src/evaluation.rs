@@ -0,0 +1,35 @@
+use crate::point::*;
+use crate::multi_fidelity::Fidelity;
+use num_traits::Float;
+use std::time::Duration;
+
+/// An evaluation of the function, as told to the optimizer.
+#[derive(Clone, Debug)]
+pub struct Evaluation<CoordFloat, ValueFloat>
+{
+    /// Coordinates, in the hypercube, at which the function was evaluated.
+    pub coordinates: Coordinates<CoordFloat>,
+    /// Value of the function (not altered, even when minimizing or when the value is not finite).
+    pub value: ValueFloat,
+    /// Values of the constraints `g_i(x) <= 0` (empty if the search is not constrained).
+    pub constraints: Box<[ValueFloat]>,
+    /// Fidelity at which the function was evaluated (see `MultiFidelityOptimizer`).
+    pub fidelity: Fidelity,
+    /// Index of the evaluation in the order in which points were asked (starting at 0).
+    pub iteration: usize,
+    /// Time elapsed between the moment the point was asked and the moment its value (or its failure) was told.
+    pub duration: Duration,
+    /// True if the evaluation failed (see `AskTellOptimizer::tell_failure`), its value is then NaN and it has no constraints.
+    pub failed: bool
+}
+
+impl<CoordFloat, ValueFloat: Float> Evaluation<CoordFloat, ValueFloat>
+{
+    /// Returns true if the evaluation satisfies all the constraints (a NaN constraint is considered violated).
+    ///
+    /// A failed evaluation is never feasible.
+    pub fn is_feasible(&self) -> bool
+    {
+        !self.failed && self.constraints.iter().all(|&g| g <= ValueFloat::zero())
+    }
+}
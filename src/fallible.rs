@@ -0,0 +1,161 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use crate::algorithm::Objective;
+use num_traits::Float;
+
+/// Stores the parameters and current state of a search whose function can fail.
+///
+/// The function returns a `Result` and failed evaluations are dealt with according to the `FailurePolicy`
+/// (see `set_failure_policy`), they never change the best value found so far.
+///
+/// - `ValueFloat` is the float type used to represent the evaluations (such as f64)
+/// - `CoordFloat` is the float type used to represent the coordinates (such as f32)
+/// - `Error` is the type of the errors returned by the function
+///
+/// ```rust
+/// # use simplers_optimization::{FallibleOptimizer, FailurePolicy};
+/// # fn main() {
+/// // the simulation diverges when the product is too large
+/// let f = |v:&[f64]| if v[0] * v[1] > 100. { Err("diverged") } else { Ok(v[0] * v[1]) };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = FallibleOptimizer::new(&f, &input_interval, should_minimize)
+///                                       .set_failure_policy(FailurePolicy::Skip);
+/// let (min_value, coordinates) = optimizer.nth(30).unwrap().unwrap();
+/// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+///
+/// for (coordinates, error) in optimizer.failures()
+/// {
+///     println!("evaluation failed in [{}, {}]: {}", coordinates[0], coordinates[1], error);
+/// }
+/// # }
+/// ```
+pub struct FallibleOptimizer<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Error>
+{
+    f: Objective<'f_lifetime, CoordFloat, Result<ValueFloat, Error>>,
+    search: AskTellOptimizer<CoordFloat, ValueFloat>,
+    /// failed evaluations with their error
+    failures: Vec<(Coordinates<CoordFloat>, Error)>,
+    /// set once the search has been aborted
+    aborted: bool
+}
+
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Error>
+    FallibleOptimizer<'f_lifetime, CoordFloat, ValueFloat, Error>
+{
+    /// Creates a new optimizer to explore the given search space with the iterator interface.
+    ///
+    /// Takes a function, a vector of intervals describing the input and a boolean describing wether it is a minimization problem (as oppozed to a miximization problem).
+    /// Each cal to the `.next()` function (cf iterator trait) will run an iteration of search and output the best result so far
+    /// or the error that aborted the search (when using `FailurePolicy::Abort`).
+    ///
+    /// **Warning:** In d dimenssions, the first call to `.next()` will perform d+1 additional evaluations (call to f) for the initialisation of the search (those should be taken into account when counting iterations).
+    pub fn new(f: impl FnMut(&[CoordFloat]) -> Result<ValueFloat, Error> + 'f_lifetime,
+               input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+               should_minimize: bool)
+               -> Self
+    {
+        let search = AskTellOptimizer::new(input_interval, should_minimize);
+        FallibleOptimizer::from_state(f, search)
+    }
+
+    /// Sets the way failed evaluations are taken into account by the search.
+    ///
+    /// With `FailurePolicy::Abort`, the first failure is returned by `.next()` and the iteration stops.
+    pub fn set_failure_policy(mut self, failure_policy: FailurePolicy) -> Self
+    {
+        self.search = self.search.set_failure_policy(failure_policy);
+        self
+    }
+
+    /// Returns the evaluations that failed, with their coordinates and error, in the order in which they happened
+    /// (an error that aborted the search is returned by `.next()` instead).
+    pub fn failures(&self) -> &[(Coordinates<CoordFloat>, Error)]
+    {
+        &self.failures
+    }
+
+    /// Creates an optimizer that resumes a search from its state.
+    ///
+    /// This is also the way to use a search configured with the methods of `AskTellOptimizer`.
+    pub fn from_state(f: impl FnMut(&[CoordFloat]) -> Result<ValueFloat, Error> + 'f_lifetime,
+                      state: AskTellOptimizer<CoordFloat, ValueFloat>)
+                      -> Self
+    {
+        FallibleOptimizer { f: Box::new(f), search: state, failures: Vec::new(), aborted: false }
+    }
+
+    /// Returns the state of the search, which can be used to resume it later with `FallibleOptimizer::from_state`.
+    pub fn state(&self) -> &AskTellOptimizer<CoordFloat, ValueFloat>
+    {
+        &self.search
+    }
+
+    /// Consumes the optimizer and returns the state of the search, which can be used to resume it later with `FallibleOptimizer::from_state`.
+    pub fn into_state(self) -> AskTellOptimizer<CoordFloat, ValueFloat>
+    {
+        self.search
+    }
+
+    /// asks for a point, evaluates it and tells its value (or failure) to the search
+    /// returns None if there is nothing left to evaluate and an error if the search should be aborted
+    fn step(&mut self) -> Option<Result<(), Error>>
+    {
+        let coordinates = self.search.ask()?;
+        match (self.f)(&coordinates)
+        {
+            Ok(value) => self.search.tell(&coordinates, value),
+            Err(error) =>
+            {
+                self.search.tell_failure(&coordinates);
+                if self.search.failure_policy == FailurePolicy::Abort
+                {
+                    self.aborted = true;
+                    return Some(Err(error));
+                }
+                self.failures.push((coordinates, error));
+            }
+        }
+        Some(Ok(()))
+    }
+}
+
+/// implements iterator for the FallibleOptimizer to give full control on the stopping condition to the user
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float, Error> Iterator
+    for FallibleOptimizer<'f_lifetime, CoordFloat, ValueFloat, Error>
+{
+    type Item = Result<(ValueFloat, Coordinates<CoordFloat>), Error>;
+
+    /// runs an iteration of the optimization algorithm and returns the best result so far
+    /// returns the error that aborted the search, if any, and None afterward
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        if self.aborted
+        {
+            return None;
+        }
+        // evaluates the corners of the initial simplex
+        while !self.search.is_initialized()
+        {
+            if let Err(error) = self.step()?
+            {
+                return Some(Err(error));
+            }
+        }
+        if let Err(error) = self.step()?
+        {
+            return Some(Err(error));
+        }
+        // there is no best point until an evaluation succeeds
+        while self.search.best_point.is_none()
+        {
+            if let Err(error) = self.step()?
+            {
+                return Some(Err(error));
+            }
+        }
+        self.search.best().map(Ok)
+    }
+}
@@ -39,6 +39,62 @@
 //!println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
 //!# }
 //!```
+//!
+//!Finally, if the function cannot be called directly (because it is evaluated by another program, on a cluster, etc),
+//!use the `AskTellOptimizer` which hands out the coordinates to evaluate and waits to be told their value :
+//!
+//!```rust
+//!# use simplers_optimization::AskTellOptimizer;
+//!# fn main() {
+//!# let f = |v:&[f64]| v[0] * v[1];
+//!let input_interval = vec![(-10., 10.), (-20., 20.)];
+//!let should_minimize = true;
+//!
+//!let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+//!for _ in 0..100
+//!{
+//!    let coordinates = optimizer.ask().unwrap();
+//!    let value = f(&coordinates); // can be done anywhere, at any time
+//!    optimizer.tell(&coordinates, value);
+//!}
+//!
+//!let (min_value, coordinates) = optimizer.best().unwrap();
+//!assert_eq!(min_value, f(&coordinates));
+//!assert!(optimizer.history().iter().all(|evaluation| min_value <= evaluation.value));
+//!# }
+//!```
+//!
+//!The input intervals can be given as tuples `(inf, sup)` or, to use other kinds of dimensions (such as integers, categories or logarithmic scales), as `Dimension`s.
+//!Dimensions that only matter for some values of a discrete dimension can be declared with `set_condition` (the function then receives NaN when they are inactive).
+//!In low dimension, `set_triangulation(Triangulation::Kuhn)` starts the search from the corners of the hypercube, keeping the density of samples uniform.
+//!
+//!Functions that can fail (returning a `Result`) can be optimized with the `FallibleOptimizer` (or `AskTellOptimizer::tell_failure`),
+//!failed evaluations are dealt with according to a `FailurePolicy`.
+//!Evaluations that are not finite (NaN or infinite) are dealt with according to a `NonFinitePolicy`.
+//!Black-box constraints `g(x) <= 0` can be added with `Optimizer::add_constraint` (or `AskTellOptimizer::tell_constrained`),
+//!the search steers away from infeasible regions and only reports feasible points.
+//!Noisy functions can be declared with `set_samples_per_point`, the best point is then evaluated several times
+//!and points are compared using the mean of their evaluations.
+//!
+//!The exploration depth can be changed at any time, or follow an `ExplorationSchedule` (exploring early and exploiting late) with `Optimizer::set_exploration_schedule`.
+//!Besides the iterator adapters, a search can be stopped by a `StopCondition` (evaluation budget, time limit, target value, etc)
+//!using `Optimizer::set_stop_condition` or the `Optimizer::minimize_until` / `Optimizer::maximize_until` functions.
+//!All the evaluations performed are available, for later analysis, with `history`.
+//!To follow a search as it runs (to log it, display it or abort it), an `Observer` can be registered with `Optimizer::add_observer`.
+//!The way simplices are scored (balancing exploration and exploitation) can be changed by implementing a `ScoringStrategy`
+//!and building the optimizer with `Optimizer::with_scoring_strategy`.
+//!
+//!Functions with several objectives (returning a `Vec` of values) can be optimized with the `MultiObjectiveOptimizer` which searches for their Pareto front.
+//!Functions that can be approximated cheaply (taking a `Fidelity`) can be optimized with the `MultiFidelityOptimizer`
+//!which screens new points at low fidelity and only evaluates the competitive ones at full fidelity.
+//!
+//!If the function is expensive and thread-safe, enabling the `parallel` feature gives access to the
+//!`Optimizer::minimize_parallel` / `Optimizer::maximize_parallel` functions which evaluate batches of points on all available threads.
+//!Similarly, the `async` feature gives access to the `Optimizer::minimize_async` / `Optimizer::maximize_async` functions
+//!which keep several evaluations of an asynchronous function in flight.
+//!
+//!Finally, the `serde` feature implements `Serialize` / `Deserialize` for the `AskTellOptimizer`,
+//!letting the user save the state of a search (obtained with `Optimizer::state`) and resume it later (with `Optimizer::from_state`).
 #![deny(missing_docs,
         //missing_debug_implementations,
         missing_copy_implementations,
@@ -50,10 +106,37 @@
         unused_qualifications)]
 
 mod point;
+mod dimension;
 mod simplex;
 mod search_space;
+mod evaluation;
+mod ask_tell;
 mod algorithm;
+mod fallible;
+mod stop;
+mod observer;
+mod multi_objective;
+mod multi_fidelity;
+mod scoring;
+mod schedule;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(feature = "serde")]
+mod serialization;
+pub use dimension::{Dimension, Categories};
+pub use search_space::Triangulation;
+pub use ask_tell::{AskTellOptimizer, PendingStrategy, FailurePolicy, NonFinitePolicy};
 pub use algorithm::Optimizer;
+pub use fallible::FallibleOptimizer;
+pub use stop::StopCondition;
+pub use evaluation::Evaluation;
+pub use observer::{Observer, SimplexInfo};
+pub use multi_objective::MultiObjectiveOptimizer;
+pub use multi_fidelity::{MultiFidelityOptimizer, Fidelity};
+pub use scoring::{ScoringStrategy, SimplexView, InverseDistanceScoring};
+pub use schedule::ExplorationSchedule;
 
 #[cfg(test)]
 mod tests
@@ -1,9 +1,4 @@
-#[allow(dead_code)]
-mod point;
-mod simplex;
-mod search_space;
-mod algorithm;
-pub use algorithm::Optimizer;
+use simplers_optimization::Optimizer;
 
 fn main()
 {
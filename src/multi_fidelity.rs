@@ -0,0 +1,239 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use num_traits::Float;
+
+/// Fidelity at which a function is evaluated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fidelity
+{
+    /// A cheap approximation of the function, used to screen points.
+    Low,
+    /// The full cost evaluation of the function, the one being optimized.
+    Full
+}
+
+/// a function, owned or borrowed for the given lifetime, that can be evaluated on coordinates at a given fidelity
+type MultiFidelityObjective<'f_lifetime, CoordFloat, ValueFloat> =
+    Box<dyn FnMut(&[CoordFloat], Fidelity) -> ValueFloat + 'f_lifetime>;
+
+/// Stores the parameters and current state of a search on a function that can be evaluated at several fidelities.
+///
+/// Each new point (the center of a simplex) is first evaluated at low fidelity,
+/// only the points whose low fidelity value is competitive are then promoted to a full fidelity evaluation
+/// (see `AskTellOptimizer::should_promote`).
+/// The other points are kept in the search with a value estimated from their low fidelity value
+/// but the best point is always a point that was evaluated at full fidelity.
+///
+/// - `ValueFloat` is the float type used to represent the evaluations (such as f64)
+/// - `CoordFloat` is the float type used to represent the coordinates (such as f32)
+///
+/// ```rust
+/// # use simplers_optimization::{MultiFidelityOptimizer, Fidelity};
+/// # fn main() {
+/// // the low fidelity evaluation could be a simulation with a coarser mesh or a training on fewer epochs
+/// let f = |v:&[f64], fidelity:Fidelity| match fidelity
+/// {
+///     Fidelity::Low => v[0] * v[1] + 10.,
+///     Fidelity::Full => v[0] * v[1] + 0.01 * v[0].powi(2)
+/// };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = MultiFidelityOptimizer::new(&f, &input_interval, should_minimize).set_promotion_quantile(0.75);
+/// let (min_value, coordinates) = optimizer.nth(100).unwrap();
+///
+/// // the best point was evaluated at full fidelity
+/// let history = optimizer.state().history();
+/// assert!(history.iter().any(|evaluation| {
+///                         evaluation.coordinates == coordinates && evaluation.fidelity == Fidelity::Full
+///                     }));
+/// assert_eq!(min_value, f(&coordinates, Fidelity::Full));
+///
+/// // while most points were only screened at low fidelity
+/// let nb_full = history.iter().filter(|evaluation| evaluation.fidelity == Fidelity::Full).count();
+/// let nb_low = history.iter().filter(|evaluation| evaluation.fidelity == Fidelity::Low).count();
+/// assert!(nb_full < nb_low);
+/// # }
+/// ```
+pub struct MultiFidelityOptimizer<'f_lifetime, CoordFloat: Float, ValueFloat: Float>
+{
+    f: MultiFidelityObjective<'f_lifetime, CoordFloat, ValueFloat>,
+    search: AskTellOptimizer<CoordFloat, ValueFloat>
+}
+
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> MultiFidelityOptimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    /// Creates a new optimizer to explore the given search space with the iterator interface.
+    ///
+    /// Takes a function taking a fidelity, a vector of intervals describing the input and a boolean describing wether the function should be minimized (as oppozed to maximized).
+    /// Each cal to the `.next()` function (cf iterator trait) will run an iteration of search and output the best result so far.
+    ///
+    /// **Warning:** In d dimenssions, the first call to `.next()` will perform d+1 additional evaluations (at both fidelities) for the initialisation of the search.
+    pub fn new(f: impl FnMut(&[CoordFloat], Fidelity) -> ValueFloat + 'f_lifetime,
+               input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+               should_minimize: bool)
+               -> Self
+    {
+        let search = AskTellOptimizer::new(input_interval, should_minimize);
+        MultiFidelityOptimizer::from_state(f, search)
+    }
+
+    /// Creates an optimizer that resumes a search from its state (see `Optimizer::from_state`).
+    pub fn from_state(f: impl FnMut(&[CoordFloat], Fidelity) -> ValueFloat + 'f_lifetime,
+                      state: AskTellOptimizer<CoordFloat, ValueFloat>)
+                      -> Self
+    {
+        MultiFidelityOptimizer { f: Box::new(f), search: state }
+    }
+
+    /// Sets the exploration depth for the algorithm (see `Optimizer::set_exploration_depth`).
+    pub fn set_exploration_depth(mut self, exploration_depth: usize) -> Self
+    {
+        self.search = self.search.set_exploration_depth(exploration_depth);
+        self
+    }
+
+    /// Sets the fraction of the full fidelity values that a low fidelity evaluation should beat to be promoted
+    /// (see `AskTellOptimizer::set_promotion_quantile`).
+    ///
+    /// Higher values promote fewer points, saving full fidelity evaluations at the risk of missing good points.
+    pub fn set_promotion_quantile(mut self, promotion_quantile: f64) -> Self
+    {
+        self.search = self.search.set_promotion_quantile(promotion_quantile);
+        self
+    }
+
+    /// Returns the state of the search, which can be used to resume it later with `MultiFidelityOptimizer::from_state`.
+    pub fn state(&self) -> &AskTellOptimizer<CoordFloat, ValueFloat>
+    {
+        &self.search
+    }
+
+    /// Consumes the optimizer and returns the state of the search, which can be used to resume it later with `MultiFidelityOptimizer::from_state`.
+    pub fn into_state(self) -> AskTellOptimizer<CoordFloat, ValueFloat>
+    {
+        self.search
+    }
+
+    /// Self contained optimization algorithm.
+    ///
+    /// Takes a function taking a fidelity, a vector of intervals describing the input and a number of iterations (each iteration performing a low fidelity evaluation).
+    /// Returns the best full fidelity value found and its coordinates.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{MultiFidelityOptimizer, Fidelity};
+    /// # use std::cell::RefCell;
+    /// # fn main() {
+    /// let evaluations = RefCell::new(Vec::new());
+    /// let f = |v:&[f64], fidelity:Fidelity|
+    /// {
+    ///     evaluations.borrow_mut().push((v.to_vec(), fidelity));
+    ///     if fidelity == Fidelity::Low { v[0] * v[1] } else { v[0] * v[1] + v[0] }
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let (min_value, coordinates) = MultiFidelityOptimizer::minimize(&f, &input_interval, nb_iterations);
+    ///
+    /// // the best point was evaluated at full fidelity, most points were only screened at low fidelity
+    /// let evaluations = evaluations.borrow();
+    /// assert!(evaluations.contains(&(coordinates.to_vec(), Fidelity::Full)));
+    /// assert_eq!(min_value, coordinates[0] * coordinates[1] + coordinates[0]);
+    /// let nb_full = evaluations.iter().filter(|(_, fidelity)| *fidelity == Fidelity::Full).count();
+    /// assert!(nb_full < evaluations.len() - nb_full);
+    /// # }
+    /// ```
+    pub fn minimize(f: impl FnMut(&[CoordFloat], Fidelity) -> ValueFloat + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                    nb_iterations: usize)
+                    -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let initial_iteration_number = input_interval.len() + 1;
+        let should_minimize = true;
+        let mut optimizer = MultiFidelityOptimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations.saturating_sub(initial_iteration_number));
+        optimizer.search.best().unwrap()
+    }
+
+    /// Self contained optimization algorithm.
+    ///
+    /// Takes a function taking a fidelity, a vector of intervals describing the input and a number of iterations (each iteration performing a low fidelity evaluation).
+    /// Returns the best full fidelity value found and its coordinates.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{MultiFidelityOptimizer, Fidelity};
+    /// # use std::cell::RefCell;
+    /// # fn main() {
+    /// let evaluations = RefCell::new(Vec::new());
+    /// let f = |v:&[f64], fidelity:Fidelity|
+    /// {
+    ///     evaluations.borrow_mut().push((v.to_vec(), fidelity));
+    ///     if fidelity == Fidelity::Low { v[0] * v[1] } else { v[0] * v[1] + v[0] }
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let (max_value, coordinates) = MultiFidelityOptimizer::maximize(&f, &input_interval, nb_iterations);
+    ///
+    /// // the best point was evaluated at full fidelity, most points were only screened at low fidelity
+    /// let evaluations = evaluations.borrow();
+    /// assert!(evaluations.contains(&(coordinates.to_vec(), Fidelity::Full)));
+    /// assert_eq!(max_value, coordinates[0] * coordinates[1] + coordinates[0]);
+    /// let nb_full = evaluations.iter().filter(|(_, fidelity)| *fidelity == Fidelity::Full).count();
+    /// assert!(nb_full < evaluations.len() - nb_full);
+    /// # }
+    /// ```
+    pub fn maximize(f: impl FnMut(&[CoordFloat], Fidelity) -> ValueFloat + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                    nb_iterations: usize)
+                    -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let initial_iteration_number = input_interval.len() + 1;
+        let should_minimize = false;
+        let mut optimizer = MultiFidelityOptimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations.saturating_sub(initial_iteration_number));
+        optimizer.search.best().unwrap()
+    }
+
+    /// asks for a point, screens it at low fidelity and promotes it to a full fidelity evaluation if it is competitive
+    /// returns None if there is nothing left to evaluate (which can only happen if all dimensions are discrete)
+    fn step(&mut self) -> Option<()>
+    {
+        // the corners of the initial simplex are always evaluated at full fidelity
+        let is_initialized = self.search.is_initialized();
+        let coordinates = self.search.ask()?;
+        let low_fidelity_value = (self.f)(&coordinates, Fidelity::Low);
+        if !is_initialized || self.search.should_promote(low_fidelity_value)
+        {
+            let value = (self.f)(&coordinates, Fidelity::Full);
+            self.search.tell_promoted(&coordinates, low_fidelity_value, value);
+        }
+        else
+        {
+            self.search.tell_low_fidelity(&coordinates, low_fidelity_value);
+        }
+        Some(())
+    }
+}
+
+/// implements iterator for the MultiFidelityOptimizer to give full control on the stopping condition to the user
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Iterator
+    for MultiFidelityOptimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    type Item = (ValueFloat, Coordinates<CoordFloat>);
+
+    /// runs an iteration of the optimization algorithm and returns the best full fidelity result so far
+    /// returns None once all the points of a discrete search space have been evaluated
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        // evaluates the corners of the initial simplex
+        while !self.search.is_initialized()
+        {
+            self.step()?;
+        }
+        self.step()?;
+        self.search.best()
+    }
+}
@@ -0,0 +1,370 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use crate::algorithm::Objective;
+use num_traits::Float;
+use std::collections::HashMap;
+
+/// Stores the parameters and current state of a search on a function with several objectives.
+///
+/// The function returns one value per objective and the optimizer searches for the Pareto front
+/// (the set of points such that no other point is at least as good on all objectives and strictly better on one of them).
+///
+/// The objectives are normalized and combined with a weighted Chebyshev scalarization
+/// (the weights following a low discrepancy sequence), the search then explores the simplex that is the most promising
+/// according to that scalarization.
+/// A new scalarization is drawn every √n iterations (n being the number of evaluations so far), which spreads the evaluations along the front
+/// while keeping the cost of rescoring the search, which is linear in its size, low.
+///
+/// - `ValueFloat` is the float type used to represent the evaluations (such as f64)
+/// - `CoordFloat` is the float type used to represent the coordinates (such as f32)
+///
+/// ```rust
+/// # use simplers_optimization::MultiObjectiveOptimizer;
+/// # fn main() {
+/// // trades accuracy (distance to 1) against latency (distance to -1)
+/// let f = |v:&[f64]| vec![(v[0] - 1.).powi(2) + v[1].powi(2), (v[0] + 1.).powi(2) + v[1].powi(2)];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = MultiObjectiveOptimizer::new(&f, &input_interval, should_minimize);
+/// optimizer.nth(100);
+///
+/// // `a` dominates `b` if it is at least as good on all objectives and strictly better on one of them
+/// let dominates = |a:&[f64], b:&[f64]|
+///     a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y);
+/// let pareto_front = optimizer.pareto_front();
+/// for (_, objectives) in &pareto_front
+/// {
+///     assert!(pareto_front.iter().all(|(_, other)| !dominates(other, objectives)));
+/// }
+/// for (_, objectives) in optimizer.evaluations()
+/// {
+///     assert!(pareto_front.iter().any(|(_, front)| front == objectives || dominates(front, objectives)));
+/// }
+/// # }
+/// ```
+pub struct MultiObjectiveOptimizer<'f_lifetime, CoordFloat: Float, ValueFloat: Float>
+{
+    f: Objective<'f_lifetime, CoordFloat, Vec<ValueFloat>>,
+    /// search on the current scalarization (which is always maximized)
+    search: AskTellOptimizer<CoordFloat, ValueFloat>,
+    minimize: bool,
+    /// all evaluations, in order, with their objectives
+    evaluations: Vec<(Coordinates<CoordFloat>, Box<[ValueFloat]>)>,
+    /// index of each evaluation, identified by its coordinates in the hypercube
+    indexes: HashMap<Vec<u64>, usize>,
+    /// indexes of the non-dominated evaluations
+    front: Vec<usize>,
+    /// scalarization used to score the search (None before the first evaluation)
+    scalarization: Option<Scalarization<ValueFloat>>,
+    /// number of scalarizations used so far
+    nb_scalarizations: usize,
+    /// number of evaluations at which the next scalarization will be drawn
+    next_scalarization: usize
+}
+
+/// a weighted Chebyshev scalarization of the objectives, normalized with the range of values seen when it was drawn
+struct Scalarization<ValueFloat: Float>
+{
+    weights: Vec<ValueFloat>,
+    /// best and worst values of each objective
+    ideal: Vec<ValueFloat>,
+    nadir: Vec<ValueFloat>,
+    minimize: bool
+}
+
+impl<ValueFloat: Float> Scalarization<ValueFloat>
+{
+    /// returns the scalarized value of the objectives (which should be maximized)
+    /// returns None if the objectives are not finite
+    fn value(&self, objectives: &[ValueFloat]) -> Option<ValueFloat>
+    {
+        if objectives.iter().any(|o| !o.is_finite())
+        {
+            return None;
+        }
+        let augmentation = ValueFloat::from(0.05).unwrap();
+        let distances = objectives.iter().enumerate().map(|(i, &o)| {
+                                                         let range = self.nadir[i] - self.ideal[i];
+                                                         // distance to the best value, 0 being the best value seen so far
+                                                         let distance = if self.minimize { o - self.ideal[i] } else { self.nadir[i] - o };
+                                                         let distance = if range > ValueFloat::zero() { distance / range } else { ValueFloat::zero() };
+                                                         self.weights[i] * distance
+                                                     });
+        let (max, sum) = distances.fold((ValueFloat::zero(), ValueFloat::zero()), |(max, sum), d| (max.max(d), sum + d));
+        Some(-(max + augmentation * sum))
+    }
+}
+
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> MultiObjectiveOptimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    /// Creates a new optimizer to explore the given search space with the iterator interface.
+    ///
+    /// Takes a function returning one value per objective (always the same number of values),
+    /// a vector of intervals describing the input and a boolean describing wether the objectives should be minimized (as oppozed to maximized).
+    /// Each cal to the `.next()` function (cf iterator trait) will run an iteration of search and output the point evaluated with its objectives.
+    ///
+    /// **Warning:** In d dimenssions, the first call to `.next()` will perform d+1 additional evaluations (call to f) for the initialisation of the search.
+    pub fn new(f: impl FnMut(&[CoordFloat]) -> Vec<ValueFloat> + 'f_lifetime,
+               input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+               should_minimize: bool)
+               -> Self
+    {
+        // the scalarizations are built such that they should be maximized
+        let search = AskTellOptimizer::new(input_interval, false);
+        MultiObjectiveOptimizer { f: Box::new(f),
+                                  search,
+                                  minimize: should_minimize,
+                                  evaluations: Vec::new(),
+                                  indexes: HashMap::new(),
+                                  front: Vec::new(),
+                                  scalarization: None,
+                                  nb_scalarizations: 0,
+                                  next_scalarization: 0 }
+    }
+
+    /// Sets the exploration depth for the algorithm (see `Optimizer::set_exploration_depth`).
+    pub fn set_exploration_depth(mut self, exploration_depth: usize) -> Self
+    {
+        self.search = self.search.set_exploration_depth(exploration_depth);
+        self
+    }
+
+    /// Returns the non-dominated points found so far, with their coordinates and objectives, in the order in which they were found.
+    pub fn pareto_front(&self) -> Vec<(Coordinates<CoordFloat>, Box<[ValueFloat]>)>
+    {
+        self.front.iter().map(|&i| self.evaluations[i].clone()).collect()
+    }
+
+    /// Returns all the evaluations performed so far, with their coordinates and objectives, in order.
+    pub fn evaluations(&self) -> &[(Coordinates<CoordFloat>, Box<[ValueFloat]>)]
+    {
+        &self.evaluations
+    }
+
+    /// Self contained optimization algorithm.
+    ///
+    /// Takes a function returning the objectives to minimize, a vector of intervals describing the input and a number of iterations.
+    /// Returns the Pareto front.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::MultiObjectiveOptimizer;
+    /// # use std::cell::RefCell;
+    /// # fn main() {
+    /// let evaluations = RefCell::new(Vec::new());
+    /// let f = |v:&[f64]|
+    /// {
+    ///     let objectives = vec![v[0] * v[1], v[0] + v[1]];
+    ///     evaluations.borrow_mut().push(objectives.clone());
+    ///     objectives
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let pareto_front = MultiObjectiveOptimizer::minimize(&f, &input_interval, nb_iterations);
+    ///
+    /// // `a` dominates `b` if it is at least as small on all objectives and strictly smaller on one of them
+    /// let dominates = |a:&[f64], b:&[f64]|
+    ///     a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y);
+    /// for (_, objectives) in &pareto_front
+    /// {
+    ///     assert!(pareto_front.iter().all(|(_, other)| !dominates(other, objectives)));
+    /// }
+    /// for objectives in evaluations.borrow().iter()
+    /// {
+    ///     assert!(pareto_front.iter().any(|(_, front)| **front == **objectives || dominates(front, objectives)));
+    /// }
+    /// # }
+    /// ```
+    pub fn minimize(f: impl FnMut(&[CoordFloat]) -> Vec<ValueFloat> + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                    nb_iterations: usize)
+                    -> Vec<(Coordinates<CoordFloat>, Box<[ValueFloat]>)>
+    {
+        let initial_iteration_number = input_interval.len() + 1;
+        let should_minimize = true;
+        let mut optimizer = MultiObjectiveOptimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations.saturating_sub(initial_iteration_number));
+        optimizer.pareto_front()
+    }
+
+    /// Self contained optimization algorithm.
+    ///
+    /// Takes a function returning the objectives to maximize, a vector of intervals describing the input and a number of iterations.
+    /// Returns the Pareto front.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::MultiObjectiveOptimizer;
+    /// # use std::cell::RefCell;
+    /// # fn main() {
+    /// let evaluations = RefCell::new(Vec::new());
+    /// let f = |v:&[f64]|
+    /// {
+    ///     let objectives = vec![v[0] * v[1], v[0] + v[1]];
+    ///     evaluations.borrow_mut().push(objectives.clone());
+    ///     objectives
+    /// };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let pareto_front = MultiObjectiveOptimizer::maximize(&f, &input_interval, nb_iterations);
+    ///
+    /// // `a` dominates `b` if it is at least as large on all objectives and strictly larger on one of them
+    /// let dominates = |a:&[f64], b:&[f64]|
+    ///     a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y);
+    /// for (_, objectives) in &pareto_front
+    /// {
+    ///     assert!(pareto_front.iter().all(|(_, other)| !dominates(other, objectives)));
+    /// }
+    /// for objectives in evaluations.borrow().iter()
+    /// {
+    ///     assert!(pareto_front.iter().any(|(_, front)| **front == **objectives || dominates(front, objectives)));
+    /// }
+    /// # }
+    /// ```
+    pub fn maximize(f: impl FnMut(&[CoordFloat]) -> Vec<ValueFloat> + 'f_lifetime,
+                    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                    nb_iterations: usize)
+                    -> Vec<(Coordinates<CoordFloat>, Box<[ValueFloat]>)>
+    {
+        let initial_iteration_number = input_interval.len() + 1;
+        let should_minimize = false;
+        let mut optimizer = MultiObjectiveOptimizer::new(f, input_interval, should_minimize);
+        optimizer.nth(nb_iterations.saturating_sub(initial_iteration_number));
+        optimizer.pareto_front()
+    }
+
+    /// asks for a point, evaluates it and tells its scalarization to the search
+    /// returns None if there is nothing left to evaluate (which can only happen if all dimensions are discrete)
+    fn step(&mut self) -> Option<(Coordinates<CoordFloat>, Box<[ValueFloat]>)>
+    {
+        let coordinates = self.search.ask()?;
+        let objectives: Box<[ValueFloat]> = (self.f)(&coordinates).into();
+        if let Some((_, first_objectives)) = self.evaluations.first()
+        {
+            assert_eq!(objectives.len(),
+                       first_objectives.len(),
+                       "The function should always return the same number of objectives!");
+        }
+        let index = self.evaluations.len();
+        self.indexes.insert(coordinates_key(&coordinates), index);
+        self.evaluations.push((coordinates.clone(), objectives.clone()));
+        self.update_front(index);
+
+        // tells the value of the point according to the current scalarization
+        // (the one used to score the search, drawing a first one when initializing the search)
+        if self.scalarization.is_none()
+        {
+            self.scalarization = Some(self.draw_scalarization());
+        }
+        let value = self.scalarization.as_ref().and_then(|s| s.value(&objectives)).unwrap_or_else(ValueFloat::nan);
+        self.search.tell(&coordinates, value);
+        Some((coordinates, objectives))
+    }
+
+    /// inserts an evaluation in the Pareto front if it is not dominated, removing the evaluations it dominates
+    fn update_front(&mut self, index: usize)
+    {
+        let objectives = &self.evaluations[index].1;
+        if objectives.iter().any(|o| !o.is_finite())
+        {
+            return;
+        }
+        let evaluations = &self.evaluations;
+        let minimize = self.minimize;
+        if self.front.iter().any(|&i| dominates(&evaluations[i].1, objectives, minimize))
+        {
+            return;
+        }
+        self.front.retain(|&i| !dominates(objectives, &evaluations[i].1, minimize));
+        self.front.push(index);
+    }
+
+    /// draws new weights and returns the corresponding scalarization
+    fn draw_scalarization(&mut self) -> Scalarization<ValueFloat>
+    {
+        let nb_objectives = self.evaluations.first().map_or(0, |(_, objectives)| objectives.len());
+        self.nb_scalarizations += 1;
+        let weights: Vec<ValueFloat> = weights(nb_objectives, self.nb_scalarizations);
+
+        // normalizes the objectives using the smallest and largest values seen so far
+        let finite_evaluations = self.evaluations.iter().map(|(_, o)| o).filter(|o| o.iter().all(|x| x.is_finite()));
+        let mut ideal = vec![ValueFloat::infinity(); nb_objectives];
+        let mut nadir = vec![ValueFloat::neg_infinity(); nb_objectives];
+        for objectives in finite_evaluations
+        {
+            for (i, &o) in objectives.iter().enumerate()
+            {
+                ideal[i] = ideal[i].min(o);
+                nadir[i] = nadir[i].max(o);
+            }
+        }
+
+        Scalarization { weights, ideal, nadir, minimize: self.minimize }
+    }
+
+    /// draws a new scalarization and rescores the search accordingly
+    fn rescalarize(&mut self)
+    {
+        let scalarization = self.draw_scalarization();
+        let evaluations = &self.evaluations;
+        let indexes = &self.indexes;
+        self.search.revalue(|coordinates| {
+                       indexes.get(&coordinates_key(coordinates)).and_then(|&i| scalarization.value(&evaluations[i].1))
+                   });
+        self.scalarization = Some(scalarization);
+
+        // the search is rescored every √n evaluations, which keeps the total cost of the rescorings in O(n√n)
+        let nb_evaluations = self.evaluations.len();
+        self.next_scalarization = nb_evaluations + ((nb_evaluations as f64).sqrt() as usize).max(1);
+    }
+}
+
+/// returns true if the first objectives are at least as good as the second on all objectives and strictly better on one of them
+fn dominates<ValueFloat: Float>(o1: &[ValueFloat], o2: &[ValueFloat], minimize: bool) -> bool
+{
+    let (o1, o2) = if minimize { (o1, o2) } else { (o2, o1) };
+    o1.iter().zip(o2.iter()).all(|(x, y)| x <= y) && o1.iter().zip(o2.iter()).any(|(x, y)| x < y)
+}
+
+/// returns the weights of the given scalarization, the weights are positive and sum to one
+/// they follow a Kronecker sequence which spreads them over the set of possible weights
+fn weights<ValueFloat: Float>(nb_objectives: usize, index: usize) -> Vec<ValueFloat>
+{
+    // uses the fractional part of the square roots of the primes as irrational steps
+    const PRIMES: [f64; 16] = [2., 3., 5., 7., 11., 13., 17., 19., 23., 29., 31., 37., 41., 43., 47., 53.];
+    let uniforms = (0..nb_objectives).map(|i| {
+                                         let step = PRIMES[i % PRIMES.len()].sqrt().fract() + (i / PRIMES.len()) as f64;
+                                         (0.5 + (index as f64) * step).fract()
+                                     });
+    // taking the log of uniform numbers and normalizing gives uniform weights on the simplex
+    let weights: Vec<f64> = uniforms.map(|u| -(u.max(f64::EPSILON)).ln()).collect();
+    let sum: f64 = weights.iter().sum();
+    weights.into_iter().map(|w| ValueFloat::from(w / sum).unwrap()).collect()
+}
+
+/// implements iterator for the MultiObjectiveOptimizer to give full control on the stopping condition to the user
+impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> Iterator
+    for MultiObjectiveOptimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    type Item = (Coordinates<CoordFloat>, Box<[ValueFloat]>);
+
+    /// runs an iteration of the optimization algorithm and returns the point evaluated with its objectives
+    /// returns None once all the points of a discrete search space have been evaluated
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        // evaluates the corners of the initial simplex
+        while !self.search.is_initialized()
+        {
+            self.step()?;
+        }
+
+        // regularly rescores the search according to a new scalarization before choosing the next point
+        if self.evaluations.len() >= self.next_scalarization
+        {
+            self.rescalarize();
+        }
+        self.step()
+    }
+}
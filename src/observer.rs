@@ -0,0 +1,116 @@
+use crate::point::*;
+use crate::evaluation::*;
+use crate::stop::*;
+use std::ops::ControlFlow;
+
+/// Describes a simplex of the search when it is split.
+#[derive(Clone, Debug)]
+pub struct SimplexInfo<CoordFloat, ValueFloat>
+{
+    /// Coordinates, in the hypercube, of the center of the simplex.
+    pub center: Coordinates<CoordFloat>,
+    /// Fraction of the search space covered by the simplex.
+    pub ratio: ValueFloat,
+    /// Score of the simplex, the simplex with the highest score is explored first.
+    pub score: ValueFloat
+}
+
+/// Receives the events of a search, see `Optimizer::add_observer`.
+///
+/// All methods do nothing by default, letting the user implement only the ones they need.
+/// The trait is also implemented for mutable references, such that an observer can be inspected once the search is over.
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, Observer, Evaluation, StopCondition};
+/// # use std::ops::ControlFlow;
+/// # use std::time::Duration;
+/// # fn main() {
+/// /// logs the progress of the search and stops it once the function has become too slow
+/// struct Monitor
+/// {
+///     nb_evaluations: usize
+/// }
+///
+/// impl Observer<f64, f64> for Monitor
+/// {
+///     fn on_evaluation(&mut self, evaluation: &Evaluation<f64, f64>) -> ControlFlow<()>
+///     {
+///         self.nb_evaluations += 1;
+///         if evaluation.duration > Duration::from_secs(1) { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+///     }
+///
+///     fn on_new_best(&mut self, value: f64, coordinates: &[f64])
+///     {
+///         println!("new best value: {} found in [{}, {}]", value, coordinates[0], coordinates[1]);
+///     }
+///
+///     fn on_stop(&mut self, stop_reason: Option<&StopCondition<f64>>)
+///     {
+///         println!("stopped after {} evaluations because of {:?}", self.nb_evaluations, stop_reason);
+///     }
+/// }
+///
+/// let f = |v:&[f64]| v[0] * v[1];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+/// let mut observer = Monitor { nb_evaluations: 0 };
+///
+/// let (min_value, coordinates) = Optimizer::new(&f, &input_interval, should_minimize)
+///                                          .add_observer(&mut observer)
+///                                          .set_stop_condition(StopCondition::MaxEvaluations(100))
+///                                          .last().unwrap();
+///
+/// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+/// assert_eq!(observer.nb_evaluations, 100);
+/// # }
+/// ```
+pub trait Observer<CoordFloat, ValueFloat>
+{
+    /// Called after each evaluation of the function.
+    ///
+    /// Returning `ControlFlow::Break` stops the search (without stop reason, see `Optimizer::stop_reason`).
+    fn on_evaluation(&mut self, _evaluation: &Evaluation<CoordFloat, ValueFloat>) -> ControlFlow<()>
+    {
+        ControlFlow::Continue(())
+    }
+
+    /// Called when the best value found so far improves, with the value and its coordinates in the hypercube.
+    fn on_new_best(&mut self, _value: ValueFloat, _coordinates: &[CoordFloat]) {}
+
+    /// Called when a simplex is split, with the simplex and the children that replace it in the search.
+    fn on_split(&mut self,
+                _parent: &SimplexInfo<CoordFloat, ValueFloat>,
+                _children: &[SimplexInfo<CoordFloat, ValueFloat>])
+    {
+    }
+
+    /// Called when the search stops with the condition that fired, if any.
+    fn on_stop(&mut self, _stop_reason: Option<&StopCondition<ValueFloat>>) {}
+}
+
+/// lets the user keep ownership of an observer
+impl<CoordFloat, ValueFloat, O: Observer<CoordFloat, ValueFloat> + ?Sized> Observer<CoordFloat, ValueFloat>
+    for &mut O
+{
+    fn on_evaluation(&mut self, evaluation: &Evaluation<CoordFloat, ValueFloat>) -> ControlFlow<()>
+    {
+        (**self).on_evaluation(evaluation)
+    }
+
+    fn on_new_best(&mut self, value: ValueFloat, coordinates: &[CoordFloat])
+    {
+        (**self).on_new_best(value, coordinates)
+    }
+
+    fn on_split(&mut self,
+                parent: &SimplexInfo<CoordFloat, ValueFloat>,
+                children: &[SimplexInfo<CoordFloat, ValueFloat>])
+    {
+        (**self).on_split(parent, children)
+    }
+
+    fn on_stop(&mut self, stop_reason: Option<&StopCondition<ValueFloat>>)
+    {
+        (**self).on_stop(stop_reason)
+    }
+}
@@ -0,0 +1,108 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::ask_tell::*;
+use crate::algorithm::Optimizer;
+use num_traits::Float;
+use rayon::prelude::*;
+
+impl<'f_lifetime, CoordFloat: Float + Send + Sync, ValueFloat: Float + Send>
+    Optimizer<'f_lifetime, CoordFloat, ValueFloat>
+{
+    /// Self contained optimization algorithm that evaluates the function on all available threads.
+    ///
+    /// Takes a function to maximize, a vector of intervals describing the input and a number of iterations.
+    /// At each step, the algorithm asks for one point per thread (see `AskTellOptimizer::ask_batch`)
+    /// and evaluates them in parallel using [rayon](https://docs.rs/rayon).
+    /// As with `Optimizer::maximize`, the function is evaluated `nb_iterations + 1` times (including the initialization).
+    ///
+    /// **Note:** This function requires the `parallel` feature.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # fn main() {
+    /// let nb_calls = AtomicUsize::new(0);
+    /// let f = |v:&[f64]| { nb_calls.fetch_add(1, Ordering::Relaxed); v[0] + v[1] };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let (max_value, coordinates) = Optimizer::maximize_parallel(&f, &input_interval, nb_iterations);
+    /// assert_eq!(nb_calls.load(Ordering::Relaxed), nb_iterations + 1);
+    /// assert!(max_value > 25.);
+    /// assert_eq!(max_value, coordinates[0] + coordinates[1]);
+    /// # }
+    /// ```
+    pub fn maximize_parallel(f: &(impl Fn(&[CoordFloat]) -> ValueFloat + Sync),
+                             input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                             nb_iterations: usize)
+                             -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let should_minimize = false;
+        optimize_parallel(f, input_interval, should_minimize, nb_iterations)
+    }
+
+    /// Self contained optimization algorithm that evaluates the function on all available threads.
+    ///
+    /// Takes a function to minimize, a vector of intervals describing the input and a number of iterations.
+    /// At each step, the algorithm asks for one point per thread (see `AskTellOptimizer::ask_batch`)
+    /// and evaluates them in parallel using [rayon](https://docs.rs/rayon).
+    /// As with `Optimizer::minimize`, the function is evaluated `nb_iterations + 1` times (including the initialization).
+    ///
+    /// **Note:** This function requires the `parallel` feature.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::Optimizer;
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # fn main() {
+    /// let nb_calls = AtomicUsize::new(0);
+    /// let f = |v:&[f64]| { nb_calls.fetch_add(1, Ordering::Relaxed); v[0] * v[1] };
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let nb_iterations = 100;
+    ///
+    /// let (min_value, coordinates) = Optimizer::minimize_parallel(&f, &input_interval, nb_iterations);
+    /// assert_eq!(nb_calls.load(Ordering::Relaxed), nb_iterations + 1);
+    /// assert!(min_value < -100.);
+    /// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+    /// # }
+    /// ```
+    pub fn minimize_parallel(f: &(impl Fn(&[CoordFloat]) -> ValueFloat + Sync),
+                             input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+                             nb_iterations: usize)
+                             -> (ValueFloat, Coordinates<CoordFloat>)
+    {
+        let should_minimize = true;
+        optimize_parallel(f, input_interval, should_minimize, nb_iterations)
+    }
+}
+
+/// runs the search, evaluating batches of points in parallel, until `nb_iterations + 1` evaluations have been done
+/// (matching the number of evaluations of the sequential `Optimizer::minimize` / `Optimizer::maximize`)
+fn optimize_parallel<CoordFloat: Float + Send + Sync, ValueFloat: Float + Send>(
+    f: &(impl Fn(&[CoordFloat]) -> ValueFloat + Sync),
+    input_interval: &[impl Into<Dimension<CoordFloat>> + Clone],
+    should_minimize: bool,
+    nb_iterations: usize)
+    -> (ValueFloat, Coordinates<CoordFloat>)
+{
+    // the constant liar strategy insures that the batches are filled and spread over the search space
+    let mut search = AskTellOptimizer::new(input_interval, should_minimize)
+                                      .set_pending_strategy(PendingStrategy::ConstantLiar);
+    let batch_size = rayon::current_num_threads();
+
+    let max_evaluations = nb_iterations + 1;
+    let mut nb_evaluations = 0;
+    while nb_evaluations < max_evaluations
+    {
+        let batch = search.ask_batch(batch_size.min(max_evaluations - nb_evaluations));
+        if batch.is_empty()
+        {
+            // all the points of a discrete search space have been evaluated
+            break;
+        }
+        let values: Vec<ValueFloat> = batch.par_iter().map(|coordinates| f(coordinates)).collect();
+        batch.iter().zip(values).for_each(|(coordinates, value)| search.tell(coordinates, value));
+        nb_evaluations += batch.len();
+    }
+
+    search.best().expect("You need at least one iteration!")
+}
@@ -1,43 +1,114 @@
-use std::rc::Rc;
+use std::sync::Arc;
 use num_traits::Float;
 
 /// Represents coordinates in space.
 pub type Coordinates<CoordFloat> = Box<[CoordFloat]>;
 
+/// Describes how a point was screened at low fidelity (see `MultiFidelityOptimizer`).
+///
+/// The low fidelity value is stored oriented such that it should be maximized (like the value of the point).
+#[derive(Clone, Copy, Debug)]
+pub enum Screening<ValueFloat>
+{
+    /// the point was only evaluated at full fidelity
+    NotScreened,
+    /// the point was evaluated at low fidelity, with the given value, then promoted to a full fidelity evaluation
+    Promoted(ValueFloat),
+    /// the point was only evaluated at low fidelity, with the given value,
+    /// the value of the point is then an estimation of its full fidelity value
+    Rejected(ValueFloat)
+}
+
 /// Represents an evaluated coordinates in space.
 #[derive(Clone, Debug)]
 pub struct Point<CoordFloat: Float, ValueFloat: Float>
 {
     pub coordinates: Coordinates<CoordFloat>,
-    pub value: ValueFloat
+    /// mean of the evaluations of the point (there is a single evaluation unless the function is noisy)
+    pub value: ValueFloat,
+    /// number of evaluations of the point
+    pub nb_samples: usize,
+    /// sum of the squared deviations of the evaluations to their mean, used to compute the variance
+    pub squared_deviations: ValueFloat,
+    /// total amount by which the constraints were violated (zero if the point is feasible)
+    pub violation: ValueFloat,
+    /// how the point was screened at low fidelity, keeping the low fidelity value separate from the value of the point
+    pub screening: Screening<ValueFloat>,
+    /// which dimensions were active when the point was evaluated (None if they all were)
+    pub active: Option<Box<[bool]>>
 }
 
 impl<CoordFloat: Float, ValueFloat: Float> Point<CoordFloat, ValueFloat>
 {
-    /// computes the euclidian distance between two sets of coordinate
-    pub fn distance(p1: &Coordinates<CoordFloat>, p2: &Coordinates<CoordFloat>) -> ValueFloat
+    /// computes the euclidian distance between the point and a set of coordinate
+    /// dimensions that are inactive, for the point or according to `other_active`, are ignored
+    /// (as the value of the point does not depend on them)
+    pub fn distance_to(&self, coordinates: &[CoordFloat], other_active: Option<&[bool]>) -> ValueFloat
     {
-        p1.iter()
-          .zip(p2.iter())
-          .map(|(&x, &y)| (x - y).powi(2))
+        let is_active = |i: usize| {
+            let is_active_for_point = self.active.as_ref().map_or(true, |active| active[i]);
+            is_active_for_point && other_active.map_or(true, |active| active[i])
+        };
+        self.coordinates
+          .iter()
+          .zip(coordinates.iter())
+          .enumerate()
+          .filter(|(i, _)| is_active(*i))
+          .map(|(_, (&x, &y))| (x - y).powi(2))
           .map(|x| ValueFloat::from(x).expect("Unable to convert from coordinate type to value type."))
           .fold(ValueFloat::zero(), ::std::ops::Add::add) // sum
           .sqrt()
     }
 
+    /// returns true if the point satisfies all the constraints
+    pub fn is_feasible(&self) -> bool
+    {
+        self.violation.is_zero()
+    }
+
+    /// returns true if the value of the point comes from a full fidelity evaluation (rather than a low fidelity estimation)
+    pub fn is_full_fidelity(&self) -> bool
+    {
+        !matches!(self.screening, Screening::Rejected(_))
+    }
+
+    /// returns the sample variance of the evaluations of the point (zero if it was evaluated once)
+    pub fn variance(&self) -> ValueFloat
+    {
+        if self.nb_samples < 2
+        {
+            ValueFloat::zero()
+        }
+        else
+        {
+            self.squared_deviations / ValueFloat::from(self.nb_samples - 1).unwrap()
+        }
+    }
+
+    /// returns a copy of the point updated with a new evaluation
+    /// (uses Welford's algorithm to update the mean and the squared deviations)
+    pub fn add_sample(&self, value: ValueFloat) -> Self
+    {
+        let nb_samples = self.nb_samples + 1;
+        let delta = value - self.value;
+        let mean = self.value + delta / ValueFloat::from(nb_samples).unwrap();
+        let squared_deviations = self.squared_deviations + delta * (value - mean);
+        Point { value: mean, nb_samples, squared_deviations, ..self.clone() }
+    }
+
     /// adds the point to the coordinates and returns the coordinates
-    fn add_to(&self, coordinates: Coordinates<CoordFloat>) -> Coordinates<CoordFloat>
+    fn add_to(&self, coordinates: &[CoordFloat]) -> Coordinates<CoordFloat>
     {
         coordinates.iter().zip(self.coordinates.iter()).map(|(&x, &y)| x + y).collect()
     }
 
     /// computes the average of the coordinates
-    pub fn average_coordinate(points: &[Rc<Point<CoordFloat, ValueFloat>>]) -> Coordinates<CoordFloat>
+    pub fn average_coordinate(points: &[Arc<Point<CoordFloat, ValueFloat>>]) -> Coordinates<CoordFloat>
     {
         let length = CoordFloat::from(points.len()).expect("Unable to convert from usize to value type.");
         let mut points = points.iter();
         let first = points.next().expect("You need at least one coordinate to average!").coordinates.clone();
-        let sum = points.fold(first, |acc, x| x.add_to(acc));
+        let sum = points.fold(first, |acc, x| x.add_to(&acc));
         sum.iter().map(|sum| (*sum) / length).collect()
     }
 }
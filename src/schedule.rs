@@ -0,0 +1,101 @@
+use crate::stop::*;
+use num_traits::Float;
+
+/// Describes how the exploration depth evolves during a search, see `Optimizer::set_exploration_schedule`.
+///
+/// Depths are given as floats (see `Optimizer::set_exploration_depth` for their meaning),
+/// a low depth early in the search favours exploration while a high depth late in the search favours exploitation.
+/// Depths should be positive, setting a schedule with a negative (or NaN) depth will panic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExplorationSchedule
+{
+    /// The depth goes linearly from `start` to `end` over the given number of evaluations, then stays at `end`.
+    Linear
+    {
+        /// depth at the beginning of the search
+        start: f64,
+        /// depth once the given number of evaluations has been performed
+        end: f64,
+        /// number of evaluations over which the depth changes
+        nb_evaluations: usize
+    },
+    /// The depth goes geometrically from `start` to `end` over the given number of evaluations, then stays at `end`.
+    ///
+    /// The depth changes slowly early in the search (when going from a low depth to a high depth).
+    Geometric
+    {
+        /// depth at the beginning of the search
+        start: f64,
+        /// depth once the given number of evaluations has been performed
+        end: f64,
+        /// number of evaluations over which the depth changes
+        nb_evaluations: usize
+    },
+    /// The depth goes linearly from `start` to `end` as the budget of the stop condition is consumed
+    /// (the fraction of the evaluations of a `StopCondition::MaxEvaluations`
+    /// or of the time of a `StopCondition::Timeout` that has been used, see `Optimizer::set_stop_condition`).
+    ///
+    /// The depth stays at `start` if the stop condition has no budget.
+    Budget
+    {
+        /// depth at the beginning of the search
+        start: f64,
+        /// depth once the budget has been consumed
+        end: f64
+    }
+}
+
+impl ExplorationSchedule
+{
+    /// panics if one of the depths is negative or NaN (which would lead to a non-positive divisor when scoring simplices)
+    pub(crate) fn assert_valid(&self)
+    {
+        let (start, end) = match *self
+        {
+            ExplorationSchedule::Linear { start, end, .. }
+            | ExplorationSchedule::Geometric { start, end, .. }
+            | ExplorationSchedule::Budget { start, end } => (start, end)
+        };
+        assert!(start >= 0. && end >= 0., "The depths of an exploration schedule should be positive!");
+    }
+
+    /// returns the exploration depth given the progress of the search and its stop condition
+    pub(crate) fn exploration_depth<ValueFloat: Float>(&self,
+                                                       progress: &Progress,
+                                                       stop_condition: Option<&StopCondition<ValueFloat>>)
+                                                       -> f64
+    {
+        match *self
+        {
+            ExplorationSchedule::Linear { start, end, nb_evaluations } =>
+            {
+                let fraction = evaluations_fraction(progress, nb_evaluations);
+                start + (end - start) * fraction
+            }
+            ExplorationSchedule::Geometric { start, end, nb_evaluations } =>
+            {
+                // interpolates between depth+1, which is strictly positive, such that a depth of zero can be used
+                let fraction = evaluations_fraction(progress, nb_evaluations);
+                (start + 1.) * ((end + 1.) / (start + 1.)).powf(fraction) - 1.
+            }
+            ExplorationSchedule::Budget { start, end } =>
+            {
+                let fraction = stop_condition.and_then(|c| c.budget_fraction(progress)).unwrap_or(0.);
+                start + (end - start) * fraction
+            }
+        }
+    }
+}
+
+/// returns the fraction, between zero and one, of the given number of evaluations that has been performed
+fn evaluations_fraction(progress: &Progress, nb_evaluations: usize) -> f64
+{
+    if nb_evaluations == 0
+    {
+        1.
+    }
+    else
+    {
+        (progress.nb_evaluations() as f64 / nb_evaluations as f64).min(1.)
+    }
+}
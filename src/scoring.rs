@@ -0,0 +1,196 @@
+use crate::simplex::*;
+use num_traits::Float;
+
+/// A simplex of the search, as seen by a `ScoringStrategy`.
+///
+/// Coordinates are expressed in the unit simplex (or the unit hypercube, see `Triangulation`) used internally by the search
+/// and values are oriented such that they should be maximized (they are negated when minimizing).
+pub struct SimplexView<'a, CoordFloat: Float, ValueFloat: Float>
+{
+    simplex: &'a Simplex<CoordFloat, ValueFloat>,
+    exploration_depth: ValueFloat,
+    nb_evaluations: usize
+}
+
+impl<'a, CoordFloat: Float, ValueFloat: Float> SimplexView<'a, CoordFloat, ValueFloat>
+{
+    /// wraps a simplex, the current exploration depth and the number of evaluations told so far
+    pub(crate) fn new(simplex: &'a Simplex<CoordFloat, ValueFloat>,
+                      exploration_depth: ValueFloat,
+                      nb_evaluations: usize)
+                      -> Self
+    {
+        SimplexView { simplex, exploration_depth, nb_evaluations }
+    }
+
+    /// Returns the number of corners of the simplex (the dimension plus one).
+    pub fn nb_corners(&self) -> usize
+    {
+        self.simplex.corners.len()
+    }
+
+    /// Returns the coordinates of the given corner.
+    pub fn corner_coordinates(&self, corner: usize) -> &[CoordFloat]
+    {
+        &self.simplex.corners[corner].coordinates
+    }
+
+    /// Returns the value of the given corner.
+    ///
+    /// Corners that violate a constraint are given a value below the worst value seen so far
+    /// (the further below the larger their violation), which steers the search away from infeasible regions.
+    pub fn corner_value(&self, corner: usize) -> ValueFloat
+    {
+        self.simplex.feasible_value(&self.simplex.corners[corner])
+    }
+
+    /// Returns the coordinates of the center of the simplex, which is where it will be evaluated.
+    pub fn center(&self) -> &[CoordFloat]
+    {
+        &self.simplex.center
+    }
+
+    /// Returns the value of the center interpolated from the values of the corners (weighted by the inverse of their distance to the center).
+    pub fn interpolate(&self) -> ValueFloat
+    {
+        self.simplex.interpolate_by(|c| self.simplex.feasible_value(c))
+    }
+
+    /// Returns the fraction of the search space covered by the simplex.
+    pub fn ratio(&self) -> ValueFloat
+    {
+        self.simplex.ratio
+    }
+
+    /// Returns the number of splits needed to reach the ratio of the simplex, starting from a regular simplex.
+    pub fn split_number(&self) -> ValueFloat
+    {
+        self.simplex.split_number()
+    }
+
+    /// Returns the difference between the best and the worst values seen so far.
+    pub fn difference(&self) -> ValueFloat
+    {
+        self.simplex.difference
+    }
+
+    /// Returns the exploration depth of the search plus one (see `Optimizer::set_exploration_depth`).
+    pub fn exploration_depth(&self) -> ValueFloat
+    {
+        self.exploration_depth
+    }
+
+    /// Returns the number of evaluations told to the search so far (including failed evaluations),
+    /// which can be used to adapt the exploration to the progress of the search, in the spirit of UCB.
+    ///
+    /// See `ScoringStrategy` for how a score that depends on it is refreshed.
+    ///
+    /// ```rust
+    /// # use simplers_optimization::{Optimizer, ScoringStrategy, SimplexView};
+    /// # fn main() {
+    /// /// as with UCB, the weight given to exploration grows with the logarithm of the number of evaluations
+    /// struct LogarithmicExploration;
+    ///
+    /// impl ScoringStrategy<f64, f64> for LogarithmicExploration
+    /// {
+    ///     fn score(&self, simplex: &SimplexView<f64, f64>) -> f64
+    ///     {
+    ///         let nb_evaluations = simplex.nb_evaluations().max(1) as f64;
+    ///         simplex.interpolate() - simplex.difference() * simplex.split_number() / (1. + nb_evaluations.ln())
+    ///     }
+    /// }
+    ///
+    /// let f = |v:&[f64]| -(v[0] - 1.).powi(2) - (v[1] + 2.).powi(2);
+    /// let input_interval = vec![(-10., 10.), (-20., 20.)];
+    /// let should_minimize = false;
+    ///
+    /// let (max_value, coordinates) = Optimizer::with_scoring_strategy(&f, &input_interval, should_minimize, LogarithmicExploration)
+    ///                                          .nth(100)
+    ///                                          .unwrap();
+    /// assert!(max_value > -1.);
+    /// println!("max value: {} found in [{}, {}]", max_value, coordinates[0], coordinates[1]);
+    /// # }
+    /// ```
+    pub fn nb_evaluations(&self) -> usize
+    {
+        self.nb_evaluations
+    }
+}
+
+/// Computes the score of a simplex, the simplex with the highest score being the next one explored by the search.
+///
+/// The score of a simplex is recomputed when it is created, when one of its corners changes
+/// and when the exploration depth changes.
+/// Simplices are also rescored lazily, when they reach the top of the queue,
+/// if the range of values (see `SimplexView::difference`) or the largest constraint violation changed since they were scored.
+///
+/// The lazy rescoring relies on a monotonicity contract: **an outdated score should never be lower than the up to date score**.
+/// A simplex whose outdated score is too high is rescored once it reaches the top of the queue and is then put back at its place,
+/// whereas a simplex whose outdated score is too low could stay burried in the queue while being the best one.
+/// The default strategy satisfies the contract as a larger range of values only increases its exploration penalty.
+///
+/// As it changes after each evaluation, the number of evaluations (see `SimplexView::nb_evaluations`) does not trigger a rescoring:
+/// a score that increases with it (such as a UCB bonus) is only refreshed along with the other triggers
+/// and the order of the queue is then approximate.
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, ScoringStrategy, SimplexView};
+/// # fn main() {
+/// /// scores a simplex with an upper bound on the value of its center, assuming the function is Lipschitz
+/// struct LipschitzBound
+/// {
+///     lipschitz_constant: f64
+/// }
+///
+/// impl ScoringStrategy<f64, f64> for LipschitzBound
+/// {
+///     fn score(&self, simplex: &SimplexView<f64, f64>) -> f64
+///     {
+///         (0..simplex.nb_corners()).map(|i| {
+///                                      let distance = simplex.corner_coordinates(i)
+///                                                            .iter()
+///                                                            .zip(simplex.center())
+///                                                            .map(|(x, c)| (x - c).powi(2))
+///                                                            .sum::<f64>()
+///                                                            .sqrt();
+///                                      simplex.corner_value(i) + self.lipschitz_constant * distance
+///                                  })
+///                                  .fold(f64::INFINITY, f64::min)
+///     }
+/// }
+///
+/// let f = |v:&[f64]| v[0] * v[1];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+/// let scoring = LipschitzBound { lipschitz_constant: 100. };
+///
+/// let (min_value, coordinates) = Optimizer::with_scoring_strategy(&f, &input_interval, should_minimize, scoring)
+///                                          .nth(100)
+///                                          .unwrap();
+/// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+/// # }
+/// ```
+pub trait ScoringStrategy<CoordFloat: Float, ValueFloat: Float>
+{
+    /// Returns the score of the simplex (higher is explored first).
+    fn score(&self, simplex: &SimplexView<CoordFloat, ValueFloat>) -> ValueFloat;
+}
+
+/// The default scoring strategy of the Simple(x) algorithm.
+///
+/// The value of the center, interpolated from the corners (see `SimplexView::interpolate`),
+/// minus an exploration term proportional to the number of splits that produced the simplex
+/// (`difference * split_number / exploration_depth`).
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InverseDistanceScoring;
+
+impl<CoordFloat: Float, ValueFloat: Float> ScoringStrategy<CoordFloat, ValueFloat> for InverseDistanceScoring
+{
+    fn score(&self, simplex: &SimplexView<CoordFloat, ValueFloat>) -> ValueFloat
+    {
+        // insures that the difference (which is positiv by construction) is non zero
+        let difference = simplex.difference() + Float::epsilon();
+        simplex.interpolate() - difference * (simplex.split_number() / simplex.exploration_depth())
+    }
+}
@@ -1,36 +1,185 @@
 use crate::point::*;
+use crate::dimension::*;
+use crate::simplex::*;
 use ordered_float::OrderedFloat;
 use num_traits::Float;
 
-/// encapsulate a function and its domain of definition
-pub struct SearchSpace<'f_lifetime, CoordFloat: Float, ValueFloat: Float>
+/// Describes how the search space is divided into simplices when the search starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Triangulation
 {
-    f: &'f_lifetime dyn Fn(&[CoordFloat]) -> ValueFloat,
-    hypercube: Vec<(CoordFloat, CoordFloat)>,
+    /// The search starts from a single simplex (d+1 evaluations) which is stretched onto the hypercube.
+    ///
+    /// This is the default, it is cheap in high dimension but the stretching is not linear:
+    /// the ratio of a simplex does not reflect its true volume in the hypercube and the corners of the hypercube are never sampled.
+    Simplex,
+    /// The hypercube is divided into d! simplices (the Kuhn, or Freudenthal, triangulation)
+    /// sharing the 2^d corners of the hypercube which are all evaluated when the search starts.
+    ///
+    /// The mapping between the simplices and the hypercube is then linear, keeping the density of samples uniform.
+    /// As the initialization cost grows quickly with the dimension, this is only recommended in low dimension.
+    Kuhn
+}
+
+/// encapsulate the domain of definition of a function
+pub struct SearchSpace<CoordFloat: Float>
+{
+    pub dimensions: Vec<Dimension<CoordFloat>>,
+    /// for each dimension, the parent dimension and the values of the parent for which the dimension is active
+    pub conditions: Vec<Option<(usize, Vec<CoordFloat>)>>,
     pub minimize: bool,
-    pub dimension: usize
+    pub dimension: usize,
+    /// how the search space is initially divided, which determines the mapping to the hypercube
+    pub triangulation: Triangulation
 }
 
-impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> SearchSpace<'f_lifetime, CoordFloat, ValueFloat>
+impl<CoordFloat: Float> SearchSpace<CoordFloat>
 {
-    /// builds a new search space that encapsulate both the function to evaluate and its domain of definition
-    pub fn new(f: &'f_lifetime impl Fn(&[CoordFloat]) -> ValueFloat,
-               hypercube: &[(CoordFloat, CoordFloat)],
-               minimize: bool)
-               -> Self
+    /// builds a new search space from the domain of definition of the function to optimize
+    pub fn new(dimensions: &[impl Into<Dimension<CoordFloat>> + Clone], minimize: bool) -> Self
+    {
+        let dimensions: Vec<Dimension<CoordFloat>> = dimensions.iter().cloned().map(Into::into).collect();
+        dimensions.iter().for_each(Dimension::assert_valid);
+        let dimension = dimensions.len();
+        let conditions = vec![None; dimension];
+        SearchSpace { dimensions, conditions, minimize, dimension, triangulation: Triangulation::Simplex }
+    }
+
+    /// returns an error message if the dimension cannot be made conditional on the parent
+    pub fn check_condition(&self, dimension: usize, parent: usize) -> Result<(), &'static str>
+    {
+        if dimension >= self.dimension || parent >= self.dimension
+        {
+            return Err("The dimensions of a condition should be part of the search space!");
+        }
+        if !self.dimensions[parent].is_discrete()
+        {
+            return Err("The parent of a condition should be a discrete dimension!");
+        }
+        // insures that the conditions do not form a cycle
+        let mut ancestor = Some(parent);
+        while let Some(a) = ancestor
+        {
+            if a == dimension
+            {
+                return Err("The conditions cannot form a cycle!");
+            }
+            ancestor = self.conditions[a].as_ref().map(|(p, _)| *p);
+        }
+        Ok(())
+    }
+
+    /// declares that a dimension is only active when its parent takes one of the given values
+    pub fn set_condition(&mut self, dimension: usize, parent: usize, parent_values: &[CoordFloat])
+    {
+        if let Err(message) = self.check_condition(dimension, parent)
+        {
+            panic!("{}", message);
+        }
+        self.conditions[dimension] = Some((parent, parent_values.to_vec()));
+    }
+
+    /// returns true if some dimensions are conditional
+    pub fn has_conditions(&self) -> bool
+    {
+        self.conditions.iter().any(Option::is_some)
+    }
+
+    /// returns true if the dimension is active given the (unmasked) coordinates in the hypercube
+    fn is_active(&self, dimension: usize, c: &[CoordFloat]) -> bool
+    {
+        match &self.conditions[dimension]
+        {
+            None => true,
+            Some((parent, values)) => values.contains(&c[*parent]) && self.is_active(*parent, c)
+        }
+    }
+
+    /// returns, for each dimension, wether it is active at the given coordinates in the unit simplex
+    /// returns None if there are no conditions (meaning that all dimensions are always active)
+    pub fn active_dimensions(&self, c: &[CoordFloat]) -> Option<Box<[bool]>>
+    {
+        if !self.has_conditions()
+        {
+            return None;
+        }
+        let c = self.to_hypercube_unmasked(c);
+        Some((0..self.dimension).map(|i| self.is_active(i, &c)).collect())
+    }
+
+    /// replaces the coordinates of inactive dimensions with NaN
+    pub fn mask_inactive(&self, c: Coordinates<CoordFloat>) -> Coordinates<CoordFloat>
     {
-        let dimension = hypercube.len();
-        SearchSpace { f, hypercube: hypercube.to_vec(), minimize, dimension }
+        if !self.has_conditions()
+        {
+            return c;
+        }
+        (0..self.dimension).map(|i| if self.is_active(i, &c) { c[i] } else { CoordFloat::nan() }).collect()
     }
 
-    /// Converts coordinates from the hypercube to the unit simplex
-    /// This fucntion is useful when one wants to suggest a point to the algorithm
+    /// returns true if the coordinates are inside the hypercube
+    /// (inactive dimensions can take any value, including NaN)
+    pub fn contains(&self, c: &[CoordFloat]) -> bool
+    {
+        (c.len() == self.dimension)
+        && c.iter()
+            .zip(self.dimensions.iter())
+            .enumerate()
+            .all(|(i, (&x, d))| d.contains(x) || ((self.conditions[i].is_some()) && !self.is_active(i, c)))
+    }
+
+    /// returns true if at least one dimension is discrete, meaning that several points might map to the same coordinates
+    pub fn is_discrete(&self) -> bool
+    {
+        self.dimensions.iter().any(|d| d.is_discrete())
+    }
+
+    /// returns all the discrete coordinates within the bounding box of the given coordinates
+    /// returns None if one of the dimensions is continuous or if there are more than `max_points` coordinates
+    pub fn coordinates_between(&self,
+                               coordinates: &[Coordinates<CoordFloat>],
+                               max_points: usize)
+                               -> Option<Vec<Coordinates<CoordFloat>>>
+    {
+        let mut result: Vec<Vec<CoordFloat>> = vec![Vec::new()];
+        for (i, dimension) in self.dimensions.iter().enumerate()
+        {
+            let values = coordinates.iter().map(|c| c[i]);
+            let min = values.clone().fold(CoordFloat::infinity(), CoordFloat::min);
+            let max = values.fold(CoordFloat::neg_infinity(), CoordFloat::max);
+            let values = dimension.values_between(min, max)?;
+            if result.len() * values.len() > max_points
+            {
+                return None;
+            }
+            result = result.iter()
+                           .flat_map(|c| {
+                               values.iter().map(move |&x| {
+                                                let mut c = c.clone();
+                                                c.push(x);
+                                                c
+                                            })
+                           })
+                           .collect();
+        }
+        Some(result.into_iter().map(Vec::into_boxed_slice).collect())
+    }
+
+    /// Converts coordinates from the hypercube to the unit simplex (or the unit hypercube for the Kuhn triangulation)
+    /// This function is useful when one wants to suggest a point to the algorithm
     /// for the formula used, see: https://math.stackexchange.com/a/385071/495073
-    pub fn to_simplex(&self, c: Coordinates<CoordFloat>) -> Coordinates<CoordFloat>
+    pub fn to_simplex(&self, c: &[CoordFloat]) -> Coordinates<CoordFloat>
     {
         // goes to the unit hypercube
+        // inactive dimensions given as NaN are placed in the middle of their interval
+        let half = CoordFloat::from(0.5).unwrap();
         let c: Coordinates<CoordFloat> =
-            c.iter().zip(self.hypercube.iter()).map(|(&x, &(inf, sup))| (x - inf) / (sup - inf)).collect();
+            c.iter().zip(self.dimensions.iter()).map(|(&x, d)| if x.is_nan() { half } else { d.to_unit(x) }).collect();
+        if self.triangulation == Triangulation::Kuhn
+        {
+            return c;
+        }
         // goes to the unit simplex
         let sum = c.iter().copied().fold(CoordFloat::zero(), ::std::ops::Add::add); // sum
         let max = c.iter()
@@ -42,9 +191,20 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> SearchSpace<'f_lifetime,
     }
 
     /// converts coordinates from the unit simplex to the hypercube
+    /// the coordinates of inactive dimensions are set to NaN
+    pub fn to_hypercube(&self, c: &[CoordFloat]) -> Coordinates<CoordFloat>
+    {
+        self.mask_inactive(self.to_hypercube_unmasked(c))
+    }
+
+    /// converts coordinates from the unit simplex (or the unit hypercube for the Kuhn triangulation) to the hypercube, ignoring conditions
     /// formula deduced from: https://math.stackexchange.com/a/385071/495073
-    pub fn to_hypercube(&self, c: Coordinates<CoordFloat>) -> Coordinates<CoordFloat>
+    pub fn to_hypercube_unmasked(&self, c: &[CoordFloat]) -> Coordinates<CoordFloat>
     {
+        if self.triangulation == Triangulation::Kuhn
+        {
+            return c.iter().zip(self.dimensions.iter()).map(|(&x, d)| d.from_unit(x)).collect();
+        }
         // gets the ratio to go from the unit hypercube to the unit simplex
         let sum = c.iter().copied().fold(CoordFloat::zero(), ::std::ops::Add::add); // sum
         let max = c.iter()
@@ -53,21 +213,30 @@ impl<'f_lifetime, CoordFloat: Float, ValueFloat: Float> SearchSpace<'f_lifetime,
                    .expect("You should have at least one coordinate.");
         let ratio = if max.is_zero() { CoordFloat::zero() } else { sum / max };
         // goes from the simplex to the target hypercube
-        c.iter().zip(self.hypercube.iter()).map(|(&x, &(inf, sup))| inf + x * ratio * (sup - inf)).collect()
+        c.iter().zip(self.dimensions.iter()).map(|(&x, d)| d.from_unit(x * ratio)).collect()
+    }
+
+    /// returns the coordinates, in the unit simplex (or unit hypercube), of the corners evaluated to start the search
+    pub fn initial_corners(&self) -> Vec<Coordinates<CoordFloat>>
+    {
+        match self.triangulation
+        {
+            Triangulation::Simplex => Simplex::<CoordFloat, CoordFloat>::initial_corners(self.dimension),
+            Triangulation::Kuhn => Simplex::<CoordFloat, CoordFloat>::hypercube_corners(self.dimension)
+        }
     }
 
-    /// takes coordinates in the unit simplex and evaluate them with the function
-    pub fn evaluate(&self, c: &Coordinates<CoordFloat>) -> ValueFloat
+    /// negates the value if we are minimizing, so that the algorithm can always maximize
+    /// (the operation is its own inverse and can thus be used to get back to the user's convention)
+    pub fn orient<ValueFloat: Float>(&self, value: ValueFloat) -> ValueFloat
     {
-        let c_hypercube = self.to_hypercube(c.clone());
-        let evaluation = (self.f)(&c_hypercube);
         if self.minimize
         {
-            -evaluation
+            -value
         }
         else
         {
-            evaluation
+            value
         }
     }
 }
@@ -0,0 +1,574 @@
+use crate::point::*;
+use crate::dimension::*;
+use crate::simplex::*;
+use crate::search_space::*;
+use crate::ask_tell::*;
+use priority_queue::PriorityQueue;
+use num_traits::Float;
+use serde::{Serialize, Deserialize, Serializer, Deserializer};
+use serde::de::Error;
+use crate::evaluation::*;
+use crate::multi_fidelity::Fidelity;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// a simplex whose corners are stored as indexes into the list of points
+#[derive(Serialize, Deserialize)]
+struct SimplexState<ValueFloat>
+{
+    corners: Vec<usize>,
+    difference: ValueState<ValueFloat>,
+    ratio: ValueFloat,
+    /// infinite if nothing has been evaluated successfully when the simplex was scored
+    min_value: ValueState<ValueFloat>,
+    max_violation: ValueState<ValueFloat>
+}
+
+/// a pending evaluation whose points are stored as indexes into the list of points
+#[derive(Serialize, Deserialize)]
+enum PendingState<CoordFloat, ValueFloat>
+{
+    Corner(Coordinates<CoordFloat>),
+    Center(SimplexState<ValueFloat>),
+    Placeholder(usize),
+    Suggestion(Coordinates<CoordFloat>),
+    Reevaluation(usize)
+}
+
+/// coordinates in the hypercube where inactive dimensions (NaN) are stored as `None`
+/// (as some formats cannot represent NaN)
+type MaskedCoordinates<CoordFloat> = Vec<Option<CoordFloat>>;
+
+/// converts coordinates in the hypercube into their serializable representation
+fn mask<CoordFloat: Float>(coordinates: &[CoordFloat]) -> MaskedCoordinates<CoordFloat>
+{
+    coordinates.iter().map(|&x| if x.is_nan() { None } else { Some(x) }).collect()
+}
+
+/// inverse of `mask`
+fn unmask<CoordFloat: Float>(coordinates: MaskedCoordinates<CoordFloat>) -> Coordinates<CoordFloat>
+{
+    coordinates.into_iter().map(|x| x.unwrap_or_else(CoordFloat::nan)).collect()
+}
+
+/// a value that might not be finite (as some formats cannot represent NaN or infinities)
+#[derive(Serialize, Deserialize)]
+enum ValueState<ValueFloat>
+{
+    Finite(ValueFloat),
+    NaN,
+    Infinity,
+    NegInfinity
+}
+
+impl<ValueFloat: Float> ValueState<ValueFloat>
+{
+    /// converts a value into its serializable representation
+    fn new(value: ValueFloat) -> Self
+    {
+        if value.is_nan()
+        {
+            ValueState::NaN
+        }
+        else if value == ValueFloat::infinity()
+        {
+            ValueState::Infinity
+        }
+        else if value == ValueFloat::neg_infinity()
+        {
+            ValueState::NegInfinity
+        }
+        else
+        {
+            ValueState::Finite(value)
+        }
+    }
+
+    /// converts the representation back into a value
+    fn value(self) -> ValueFloat
+    {
+        match self
+        {
+            ValueState::Finite(value) => value,
+            ValueState::NaN => ValueFloat::nan(),
+            ValueState::Infinity => ValueFloat::infinity(),
+            ValueState::NegInfinity => ValueFloat::neg_infinity()
+        }
+    }
+}
+
+/// the screening of a point, whose low fidelity value might not be finite
+#[derive(Serialize, Deserialize)]
+enum ScreeningState<ValueFloat>
+{
+    NotScreened,
+    Promoted(ValueState<ValueFloat>),
+    Rejected(ValueState<ValueFloat>)
+}
+
+impl<ValueFloat: Float> ScreeningState<ValueFloat>
+{
+    /// converts a screening into its serializable representation
+    fn new(screening: Screening<ValueFloat>) -> Self
+    {
+        match screening
+        {
+            Screening::NotScreened => ScreeningState::NotScreened,
+            Screening::Promoted(low) => ScreeningState::Promoted(ValueState::new(low)),
+            Screening::Rejected(low) => ScreeningState::Rejected(ValueState::new(low))
+        }
+    }
+
+    /// converts the representation back into a screening
+    fn screening(self) -> Screening<ValueFloat>
+    {
+        match self
+        {
+            ScreeningState::NotScreened => Screening::NotScreened,
+            ScreeningState::Promoted(low) => Screening::Promoted(low.value()),
+            ScreeningState::Rejected(low) => Screening::Rejected(low.value())
+        }
+    }
+}
+
+/// a point of the search, its active dimensions are not stored as they can be deduced from the coordinates
+#[derive(Serialize, Deserialize)]
+struct PointState<CoordFloat, ValueFloat>
+{
+    coordinates: Coordinates<CoordFloat>,
+    value: ValueState<ValueFloat>,
+    nb_samples: usize,
+    squared_deviations: ValueState<ValueFloat>,
+    /// might be infinite
+    violation: ValueState<ValueFloat>,
+    screening: ScreeningState<ValueFloat>
+}
+
+/// an evaluation of the history
+#[derive(Serialize, Deserialize)]
+struct EvaluationState<CoordFloat, ValueFloat>
+{
+    coordinates: MaskedCoordinates<CoordFloat>,
+    value: ValueState<ValueFloat>,
+    constraints: Vec<ValueState<ValueFloat>>,
+    fidelity: Fidelity,
+    iteration: usize,
+    duration: Duration,
+    failed: bool
+}
+
+/// a pending evaluation with the number of points asked before it and the time elapsed since it was asked
+#[derive(Serialize, Deserialize)]
+struct AskedState<CoordFloat, ValueFloat>
+{
+    coordinates: MaskedCoordinates<CoordFloat>,
+    pending: PendingState<CoordFloat, ValueFloat>,
+    iteration: usize,
+    elapsed: Duration
+}
+
+/// flat representation of an `AskTellOptimizer`
+///
+/// points are shared between simplices, they are thus stored once and referenced by their index
+/// which lets us rebuild the sharing when loading the state
+#[derive(Serialize, Deserialize)]
+struct OptimizerState<CoordFloat: Float, ValueFloat, Scoring>
+{
+    exploration_depth: ValueFloat,
+    scoring: Scoring,
+    pending_strategy: PendingStrategy,
+    samples_per_point: usize,
+    triangulation: Triangulation,
+    promotion_quantile: f64,
+    fidelity_offset: ValueFloat,
+    nb_promoted: usize,
+    failure_policy: FailurePolicy,
+    non_finite_policy: NonFinitePolicy,
+    nb_non_finite: usize,
+    dimensions: Vec<Dimension<CoordFloat>>,
+    conditions: Vec<Option<(usize, Vec<CoordFloat>)>>,
+    minimize: bool,
+    points: Vec<PointState<CoordFloat, ValueFloat>>,
+    initial_coordinates: Vec<Coordinates<CoordFloat>>,
+    initial_corners: Vec<usize>,
+    failed_corners: Vec<Coordinates<CoordFloat>>,
+    fallback_points: Vec<usize>,
+    suggestions: Vec<MaskedCoordinates<CoordFloat>>,
+    postponed: Vec<usize>,
+    evaluations: Vec<(Vec<u64>, ValueState<ValueFloat>, ValueState<ValueFloat>)>,
+    pending: Vec<AskedState<CoordFloat, ValueFloat>>,
+    history: Vec<EvaluationState<CoordFloat, ValueFloat>>,
+    nb_asked: usize,
+    best_point: Option<usize>,
+    /// `None` before the first evaluation (as some formats cannot represent the infinity used as initial value)
+    min_value: Option<ValueFloat>,
+    max_value: Option<ValueFloat>,
+    max_violation: ValueState<ValueFloat>,
+    queue: Vec<(SimplexState<ValueFloat>, ValueState<ValueFloat>)>
+}
+
+/// associates an index to each distinct point (identified by its address)
+struct PointIndexer<'a, CoordFloat: Float, ValueFloat: Float>
+{
+    indexes: HashMap<*const Point<CoordFloat, ValueFloat>, usize>,
+    points: Vec<&'a Point<CoordFloat, ValueFloat>>
+}
+
+impl<'a, CoordFloat: Float, ValueFloat: Float> PointIndexer<'a, CoordFloat, ValueFloat>
+{
+    /// returns the index of the point, adding it to the list if it is not known yet
+    fn index(&mut self, point: &'a Arc<Point<CoordFloat, ValueFloat>>) -> usize
+    {
+        let points = &mut self.points;
+        *self.indexes.entry(Arc::as_ptr(point)).or_insert_with(|| {
+                                                   points.push(point);
+                                                   points.len() - 1
+                                               })
+    }
+
+    /// converts a simplex into its flat representation
+    fn simplex(&mut self, simplex: &'a Simplex<CoordFloat, ValueFloat>) -> SimplexState<ValueFloat>
+    {
+        let corners = simplex.corners.iter().map(|c| self.index(c)).collect();
+        SimplexState { corners,
+                       difference: ValueState::new(simplex.difference),
+                       ratio: simplex.ratio,
+                       min_value: ValueState::new(simplex.min_value),
+                       max_violation: ValueState::new(simplex.max_violation) }
+    }
+}
+
+/// gets the point with the given index, failing if the index is invalid
+fn get_point<CoordFloat: Float, ValueFloat: Float, E: Error>(points: &[Arc<Point<CoordFloat, ValueFloat>>],
+                                                               index: usize)
+                                                               -> Result<Arc<Point<CoordFloat, ValueFloat>>, E>
+{
+    points.get(index).cloned().ok_or_else(|| E::custom(format!("invalid point index {}", index)))
+}
+
+/// rebuilds a simplex, of the given dimension, from its flat representation
+fn get_simplex<CoordFloat: Float, ValueFloat: Float, E: Error>(points: &[Arc<Point<CoordFloat, ValueFloat>>],
+                                                                 simplex: SimplexState<ValueFloat>,
+                                                                 dimension: usize)
+                                                                 -> Result<Simplex<CoordFloat, ValueFloat>, E>
+{
+    let corners = simplex.corners.into_iter().map(|i| get_point(points, i)).collect::<Result<Vec<_>, E>>()?;
+    if corners.len() != dimension + 1
+    {
+        return Err(E::custom(format!("a simplex of dimension {} needs {} corners but has {}",
+                                     dimension,
+                                     dimension + 1,
+                                     corners.len())));
+    }
+    let mut result = Simplex::new(corners, simplex.ratio, simplex.difference.value());
+    result.min_value = simplex.min_value.value();
+    result.max_violation = simplex.max_violation.value();
+    Ok(result)
+}
+
+/// checks that some coordinates have one value per dimension
+fn check_length<E: Error>(length: usize, dimension: usize) -> Result<(), E>
+{
+    if length == dimension
+    {
+        Ok(())
+    }
+    else
+    {
+        Err(E::custom(format!("coordinates of length {} in a search space of dimension {}", length, dimension)))
+    }
+}
+
+/// Saves the full state of the search, including its scoring strategy.
+///
+/// **Note:** This implementation requires the `serde` feature.
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, AskTellOptimizer};
+/// # fn main() {
+/// let f = |v:&[f64]| v[0] * v[1];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// // runs 30 iterations then saves the state of the search
+/// let mut optimizer = Optimizer::new(&f, &input_interval, should_minimize);
+/// optimizer.nth(30);
+/// let checkpoint = serde_json::to_string(optimizer.state()).unwrap();
+///
+/// // loads the state and resumes the search for 30 more iterations
+/// let state: AskTellOptimizer<f64, f64> = serde_json::from_str(&checkpoint).unwrap();
+/// let (min_value, coordinates) = Optimizer::from_state(&f, state).nth(30).unwrap();
+/// println!("min value: {} found in [{}, {}]", min_value, coordinates[0], coordinates[1]);
+/// # }
+/// ```
+impl<CoordFloat: Float + Serialize, ValueFloat: Float + Serialize, Scoring: Serialize> Serialize
+    for AskTellOptimizer<CoordFloat, ValueFloat, Scoring>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        let mut indexer = PointIndexer { indexes: HashMap::new(), points: Vec::new() };
+        let initial_corners = self.initial_corners.iter().map(|c| indexer.index(c)).collect();
+        let postponed = self.postponed.iter().map(|p| indexer.index(p)).collect();
+        let fallback_points = self.fallback_points.iter().map(|c| indexer.index(c)).collect();
+        let pending = self.pending
+                          .iter()
+                          .map(|(coordinates, pending, asked)| {
+                              let pending = match pending
+                              {
+                                  Pending::Corner(corner) => PendingState::Corner(corner.clone()),
+                                  Pending::Center(simplex) => PendingState::Center(indexer.simplex(simplex)),
+                                  Pending::Placeholder(point) => PendingState::Placeholder(indexer.index(point)),
+                                  Pending::Suggestion(point) => PendingState::Suggestion(point.clone()),
+                                  Pending::Reevaluation(point) => PendingState::Reevaluation(indexer.index(point))
+                              };
+                              AskedState { coordinates: mask(coordinates),
+                                           pending,
+                                           iteration: asked.iteration,
+                                           elapsed: asked.time.elapsed() }
+                          })
+                          .collect();
+        let history = self.history
+                          .iter()
+                          .map(|evaluation| EvaluationState { coordinates: mask(&evaluation.coordinates),
+                                                              value: ValueState::new(evaluation.value),
+                                                              constraints: evaluation.constraints
+                                                                                     .iter()
+                                                                                     .map(|&g| ValueState::new(g))
+                                                                                     .collect(),
+                                                              fidelity: evaluation.fidelity,
+                                                              iteration: evaluation.iteration,
+                                                              duration: evaluation.duration,
+                                                              failed: evaluation.failed })
+                          .collect();
+        let best_point = self.best_point.as_ref().map(|p| indexer.index(p));
+        let queue = self.queue
+                        .iter()
+                        .map(|(simplex, (score, _))| (indexer.simplex(simplex), ValueState::new(score.0)))
+                        .collect();
+        let points = indexer.points
+                            .iter()
+                            .map(|p| PointState { coordinates: p.coordinates.clone(),
+                                                  value: ValueState::new(p.value),
+                                                  nb_samples: p.nb_samples,
+                                                  squared_deviations: ValueState::new(p.squared_deviations),
+                                                  violation: ValueState::new(p.violation),
+                                                  screening: ScreeningState::new(p.screening) })
+                            .collect();
+
+        OptimizerState { exploration_depth: self.exploration_depth,
+                         scoring: &self.scoring,
+                         pending_strategy: self.pending_strategy,
+                         samples_per_point: self.samples_per_point,
+                         triangulation: self.search_space.triangulation,
+                         promotion_quantile: self.promotion_quantile,
+                         fidelity_offset: self.fidelity_offset,
+                         nb_promoted: self.nb_promoted,
+                         failure_policy: self.failure_policy,
+                         non_finite_policy: self.non_finite_policy,
+                         nb_non_finite: self.nb_non_finite,
+                         dimensions: self.search_space.dimensions.clone(),
+                         conditions: self.search_space.conditions.clone(),
+                         minimize: self.search_space.minimize,
+                         points,
+                         initial_coordinates: self.initial_coordinates.clone(),
+                         initial_corners,
+                         failed_corners: self.failed_corners.clone(),
+                         fallback_points,
+                         suggestions: self.suggestions.iter().map(|c| mask(c)).collect(),
+                         postponed,
+                         evaluations: self.evaluations
+                                          .iter()
+                                          .map(|(k, &(v, violation))| {
+                                              (k.clone(), ValueState::new(v), ValueState::new(violation))
+                                          })
+                                          .collect(),
+                         pending,
+                         history,
+                         nb_asked: self.nb_asked,
+                         best_point,
+                         min_value: Some(self.min_value).filter(|v| v.is_finite()),
+                         max_value: Some(self.max_value).filter(|v| v.is_finite()),
+                         max_violation: ValueState::new(self.max_violation),
+                         queue }.serialize(serializer)
+    }
+}
+
+/// Loads the full state of a search, the loaded search then behaves exactly like the saved one.
+///
+/// **Note:** This implementation requires the `serde` feature.
+///
+/// **Warning:** The format needs to read back floats exactly (with `serde_json`, this requires its `float_roundtrip` feature)
+/// otherwise the loaded search might differ slightly from the saved one.
+///
+/// ```rust
+/// # use simplers_optimization::AskTellOptimizer;
+/// # fn main() {
+/// let f = |v:&[f64]| v[0] * v[1];
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+/// let should_minimize = true;
+///
+/// let mut optimizer = AskTellOptimizer::new(&input_interval, should_minimize);
+/// for _ in 0..30
+/// {
+///     let coordinates = optimizer.ask().unwrap();
+///     optimizer.tell(&coordinates, f(&coordinates));
+/// }
+///
+/// // saves then loads the state of the search
+/// let checkpoint = serde_json::to_string(&optimizer).unwrap();
+/// let mut loaded: AskTellOptimizer<f64, f64> = serde_json::from_str(&checkpoint).unwrap();
+///
+/// // both searches propose the same points
+/// for _ in 0..30
+/// {
+///     let coordinates = optimizer.ask().unwrap();
+///     assert_eq!(loaded.ask().unwrap(), coordinates);
+///     optimizer.tell(&coordinates, f(&coordinates));
+///     loaded.tell(&coordinates, f(&coordinates));
+/// }
+/// assert_eq!(loaded.best(), optimizer.best());
+/// # }
+/// ```
+impl<'de, CoordFloat: Float + Deserialize<'de>, ValueFloat: Float + Deserialize<'de>, Scoring: Deserialize<'de>>
+    Deserialize<'de> for AskTellOptimizer<CoordFloat, ValueFloat, Scoring>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        let state = OptimizerState::<CoordFloat, ValueFloat, Scoring>::deserialize(deserializer)?;
+        for dimension in &state.dimensions
+        {
+            dimension.check().map_err(D::Error::custom)?;
+        }
+        let mut search_space = SearchSpace::new(&state.dimensions, state.minimize);
+        search_space.triangulation = state.triangulation;
+        if (state.triangulation == Triangulation::Kuhn) && (search_space.dimension >= usize::BITS as usize)
+        {
+            return Err(D::Error::custom("the Kuhn triangulation needs fewer dimensions than the number of bits of a usize"));
+        }
+        if state.conditions.len() != search_space.dimension
+        {
+            return Err(D::Error::custom("there should be one condition per dimension"));
+        }
+        for (dimension, condition) in state.conditions.into_iter().enumerate()
+        {
+            if let Some((parent, active_values)) = condition
+            {
+                search_space.check_condition(dimension, parent).map_err(D::Error::custom)?;
+                search_space.set_condition(dimension, parent, &active_values);
+            }
+        }
+        let dimension = search_space.dimension;
+        for coordinates in state.initial_coordinates.iter().chain(&state.failed_corners)
+        {
+            check_length::<D::Error>(coordinates.len(), dimension)?;
+        }
+        for coordinates in &state.suggestions
+        {
+            check_length::<D::Error>(coordinates.len(), dimension)?;
+        }
+        let points: Vec<_> = state.points
+                                  .into_iter()
+                                  .map(|PointState { coordinates,
+                                                     value,
+                                                     nb_samples,
+                                                     squared_deviations,
+                                                     violation,
+                                                     screening }| {
+                                      check_length(coordinates.len(), dimension)?;
+                                      let active = search_space.active_dimensions(&coordinates);
+                                      Ok(Arc::new(Point { coordinates,
+                                                          value: value.value(),
+                                                          nb_samples,
+                                                          squared_deviations: squared_deviations.value(),
+                                                          violation: violation.value(),
+                                                          screening: screening.screening(),
+                                                          active }))
+                                  })
+                                  .collect::<Result<_, _>>()?;
+
+        let initial_corners =
+            state.initial_corners.into_iter().map(|i| get_point(&points, i)).collect::<Result<_, _>>()?;
+        let postponed = state.postponed.into_iter().map(|i| get_point(&points, i)).collect::<Result<_, _>>()?;
+        let fallback_points =
+            state.fallback_points.into_iter().map(|i| get_point(&points, i)).collect::<Result<_, _>>()?;
+        let now = Instant::now();
+        let pending = state.pending
+                           .into_iter()
+                           .map(|AskedState { coordinates, pending, iteration, elapsed }| {
+                               check_length(coordinates.len(), dimension)?;
+                               let pending = match pending
+                               {
+                                   PendingState::Corner(corner) =>
+                                   {
+                                       check_length(corner.len(), dimension)?;
+                                       Pending::Corner(corner)
+                                   }
+                                   PendingState::Center(simplex) => Pending::Center(get_simplex(&points, simplex, dimension)?),
+                                   PendingState::Placeholder(i) => Pending::Placeholder(get_point(&points, i)?),
+                                   PendingState::Suggestion(point) =>
+                                   {
+                                       check_length(point.len(), dimension)?;
+                                       Pending::Suggestion(point)
+                                   }
+                                   PendingState::Reevaluation(i) => Pending::Reevaluation(get_point(&points, i)?)
+                               };
+                               // the time elapsed since the point was asked is approximated when the state is loaded
+                               let time = now.checked_sub(elapsed).unwrap_or(now);
+                               Ok((unmask(coordinates), pending, Asked { iteration, time }))
+                           })
+                           .collect::<Result<_, _>>()?;
+        let history = state.history
+                           .into_iter()
+                           .map(|evaluation| Evaluation { coordinates: unmask(evaluation.coordinates),
+                                                          value: evaluation.value.value(),
+                                                          constraints: evaluation.constraints
+                                                                                 .into_iter()
+                                                                                 .map(ValueState::value)
+                                                                                 .collect(),
+                                                          fidelity: evaluation.fidelity,
+                                                          iteration: evaluation.iteration,
+                                                          duration: evaluation.duration,
+                                                          failed: evaluation.failed })
+                           .collect();
+        let best_point = state.best_point.map(|i| get_point(&points, i)).transpose()?;
+        let mut queue = PriorityQueue::with_capacity(state.queue.len());
+        for (simplex, score) in state.queue
+        {
+            let simplex = get_simplex(&points, simplex, dimension)?;
+            let priority = simplex.priority(score.value());
+            queue.push(simplex, priority);
+        }
+
+        Ok(AskTellOptimizer { exploration_depth: state.exploration_depth,
+                              scoring: state.scoring,
+                              pending_strategy: state.pending_strategy,
+                              samples_per_point: state.samples_per_point,
+                              promotion_quantile: state.promotion_quantile,
+                              fidelity_offset: state.fidelity_offset,
+                              nb_promoted: state.nb_promoted,
+                              failure_policy: state.failure_policy,
+                              non_finite_policy: state.non_finite_policy,
+                              nb_non_finite: state.nb_non_finite,
+                              search_space,
+                              initial_coordinates: state.initial_coordinates,
+                              initial_corners,
+                              failed_corners: state.failed_corners,
+                              fallback_points,
+                              suggestions: state.suggestions.into_iter().map(unmask).collect(),
+                              postponed,
+                              evaluations: state.evaluations
+                                                 .into_iter()
+                                                 .map(|(k, v, violation)| (k, (v.value(), violation.value())))
+                                                 .collect(),
+                              pending,
+                              history,
+                              nb_asked: state.nb_asked,
+                              splits: None,
+                              best_point,
+                              min_value: state.min_value.unwrap_or_else(ValueFloat::infinity),
+                              max_value: state.max_value.unwrap_or_else(ValueFloat::neg_infinity),
+                              max_violation: state.max_violation.value(),
+                              queue })
+    }
+}
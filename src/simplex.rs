@@ -1,67 +1,138 @@
 use crate::point::*;
-use crate::search_space::*;
 use std::hash::{Hash, Hasher};
-use std::rc::Rc;
+use std::sync::Arc;
+use ordered_float::OrderedFloat;
 use num_traits::Float;
 
+/// priority of a simplex in the queue: its score, ties being broken by the coordinates of its center
+/// so that the order in which simplices are popped does not depend on the layout of the queue
+/// (which is not preserved when a search is saved and loaded)
+pub type Priority<CoordFloat, ValueFloat> = (OrderedFloat<ValueFloat>, Box<[OrderedFloat<CoordFloat>]>);
+
 /// represents a simplex
 #[derive(Clone)]
 pub struct Simplex<CoordFloat: Float, ValueFloat: Float>
 {
     /// the coordinate+evaluations of the corners of the simplex
-    pub corners: Vec<Rc<Point<CoordFloat, ValueFloat>>>,
+    pub corners: Vec<Arc<Point<CoordFloat, ValueFloat>>>,
     /// the coordinates of the center of the simplex (which is where it is evaluated)
     pub center: Coordinates<CoordFloat>,
     /// what was the difference between the best value and the worst value when the simplex was last evaluated ?
     pub difference: ValueFloat,
+    /// what was the worst value when the simplex was last evaluated ?
+    pub min_value: ValueFloat,
     /// which fraction of the original simplex does this simplex represents ?
-    ratio: ValueFloat
+    pub ratio: ValueFloat,
+    /// what was the largest constraint violation when the simplex was last evaluated ?
+    pub max_violation: ValueFloat
 }
 
 impl<CoordFloat: Float, ValueFloat: Float> Simplex<CoordFloat, ValueFloat>
 {
     /// creates a new simplex
-    fn new(corners: Vec<Rc<Point<CoordFloat, ValueFloat>>>, ratio: ValueFloat, difference: ValueFloat)
+    pub fn new(corners: Vec<Arc<Point<CoordFloat, ValueFloat>>>, ratio: ValueFloat, difference: ValueFloat)
            -> Self
     {
         let center = Point::average_coordinate(&corners);
-        Simplex { corners, center, ratio, difference }
+        // the range of values and violations are set when the simplex is evaluated by the search
+        Simplex { corners,
+                  center,
+                  ratio,
+                  difference,
+                  min_value: ValueFloat::zero(),
+                  max_violation: ValueFloat::zero() }
+    }
+
+    /// returns the priority of the simplex in the queue given its score
+    pub fn priority(&self, score: ValueFloat) -> Priority<CoordFloat, ValueFloat>
+    {
+        (OrderedFloat(score), self.center.iter().map(|&x| OrderedFloat(x)).collect())
     }
 
-    /// builds the initial unit simplex with one point per axis plus an origin at zero
-    pub fn initial_simplex(search_space: &SearchSpace<CoordFloat, ValueFloat>) -> Self
+    /// returns the corners of the initial unit simplex, one point per axis plus an origin at zero
+    pub fn initial_corners(dimension: usize) -> Vec<Coordinates<CoordFloat>>
     {
         // origin, a vector of zero
-        let origin = vec![CoordFloat::zero(); search_space.dimension].into_boxed_slice();
+        let origin = vec![CoordFloat::zero(); dimension].into_boxed_slice();
 
         // builds one corner per dimension
-        let mut corners: Vec<_> = (0..search_space.dimension).map(|i| {
-                                                                 let mut coordinates = origin.clone();
-                                                                 coordinates[i] = CoordFloat::one();
-                                                                 let value =
-                                                                     search_space.evaluate(&coordinates);
-                                                                 Rc::new(Point { coordinates, value })
-                                                             })
-                                                             .collect();
+        let mut corners: Vec<_> = (0..dimension).map(|i| {
+                                                    let mut coordinates = origin.clone();
+                                                    coordinates[i] = CoordFloat::one();
+                                                    coordinates
+                                                })
+                                                .collect();
 
         // adds the corner corresponding to the origin
-        let min_corner = Point { value: search_space.evaluate(&origin), coordinates: origin };
-        corners.push(Rc::new(min_corner));
+        corners.push(origin);
+        corners
+    }
 
-        // assemble the simplex
+    /// builds the initial unit simplex from its evaluated corners (as produced by `initial_corners`)
+    pub fn initial_simplex(corners: Vec<Arc<Point<CoordFloat, ValueFloat>>>) -> Self
+    {
         Simplex::new(corners, ValueFloat::one(), ValueFloat::zero())
     }
 
+    /// returns the 2^d corners of the unit hypercube
+    /// panics if 2^d cannot be represented (the corners are indexed by the bits of a usize)
+    pub fn hypercube_corners(dimension: usize) -> Vec<Coordinates<CoordFloat>>
+    {
+        assert!(dimension < usize::BITS as usize,
+                "The Kuhn triangulation needs fewer than {} dimensions (it evaluates the 2^d corners of the hypercube)!",
+                usize::BITS);
+        (0..1usize << dimension).map(|bits| {
+                                   (0..dimension).map(|i| if (bits >> i) & 1 == 1 { CoordFloat::one() } else { CoordFloat::zero() })
+                                                 .collect()
+                               })
+                               .collect()
+    }
+
+    /// divides the unit hypercube into d! simplices, from its evaluated corners (as produced by `hypercube_corners`),
+    /// using the Kuhn triangulation: each permutation of the axis gives the simplex going from the origin
+    /// to the opposite corner by moving along one axis at a time, in the order of the permutation
+    /// the simplices all have the same volume, their ratio is thus the fraction of the hypercube they occupy
+    pub fn kuhn_simplices(corners: Vec<Arc<Point<CoordFloat, ValueFloat>>>, difference: ValueFloat) -> Vec<Self>
+    {
+        let dimension = corners[0].coordinates.len();
+        // finds the corners of the hypercube by their coordinates (which are all zero or one)
+        let half = CoordFloat::from(0.5).unwrap();
+        let index_of = |coordinates: &[CoordFloat]| {
+            coordinates.iter().enumerate().filter(|(_, &x)| x > half).map(|(i, _)| 1usize << i).sum::<usize>()
+        };
+        let mut corner_of_index = vec![None; corners.len()];
+        for corner in corners
+        {
+            let index = index_of(&corner.coordinates);
+            corner_of_index[index] = Some(corner);
+        }
+
+        let nb_simplices: usize = (1..=dimension).product();
+        let ratio = ValueFloat::one() / ValueFloat::from(nb_simplices).unwrap();
+        permutations(dimension).into_iter()
+                               .map(|permutation| {
+                                   let mut index = 0;
+                                   let mut simplex_corners = vec![corner_of_index[index].clone().unwrap()];
+                                   for axis in permutation
+                                   {
+                                       index |= 1 << axis;
+                                       simplex_corners.push(corner_of_index[index].clone().unwrap());
+                                   }
+                                   Simplex::new(simplex_corners, ratio, difference)
+                               })
+                               .collect()
+    }
+
     /// takes a simplex and splits it around a point
     /// difference is the best value so far minus the worst value so far
-    pub fn split(self, new_point: Rc<Point<CoordFloat, ValueFloat>>, difference: ValueFloat) -> Vec<Self>
+    pub fn split(self, new_point: Arc<Point<CoordFloat, ValueFloat>>, difference: ValueFloat) -> Vec<Self>
     {
         // computes the distance between the new point and each corners of the simplex
-        let distances: Box<[ValueFloat]> = self.corners
-                                               .iter()
-                                               .map(|c| &c.coordinates)
-                                               .map(|c| Point::distance(c, &new_point.coordinates))
-                                               .collect();
+        let distances: Box<[ValueFloat]> =
+            self.corners
+                .iter()
+                .map(|c| c.distance_to(&new_point.coordinates, new_point.active.as_deref()))
+                .collect();
         let total_distance: ValueFloat =
             distances.iter().copied().fold(ValueFloat::zero(), ::std::ops::Add::add);
 
@@ -89,37 +160,166 @@ impl<CoordFloat: Float, ValueFloat: Float> Simplex<CoordFloat, ValueFloat>
         result
     }
 
-    /// returns a score for a simplex
-    pub fn evaluate(&self, exploration_depth: ValueFloat) -> ValueFloat
+    /// computes the barycentric coordinates of a point in the simplex
+    /// (the point is inside the simplex if and only if all of them are positive)
+    /// returns None if the simplex is degenerated
+    pub fn barycentric_coordinates(&self, coordinates: &[CoordFloat]) -> Option<Vec<CoordFloat>>
+    {
+        // builds the system `sum_i w_i (corner_i - last_corner) = coordinates - last_corner`
+        let dim = coordinates.len();
+        let last_corner = &self.corners[dim].coordinates;
+        let mut matrix: Vec<Vec<CoordFloat>> =
+            (0..dim).map(|row| {
+                        let mut line: Vec<CoordFloat> =
+                            self.corners[..dim].iter().map(|c| c.coordinates[row] - last_corner[row]).collect();
+                        line.push(coordinates[row] - last_corner[row]);
+                        line
+                    })
+                    .collect();
+
+        // solves it with a gauss-jordan elimination with partial pivoting
+        for col in 0..dim
+        {
+            let pivot = (col..dim).max_by_key(|&row| OrderedFloat(matrix[row][col].abs()))?;
+            if matrix[pivot][col].is_zero()
+            {
+                return None;
+            }
+            matrix.swap(col, pivot);
+            let pivot_line = matrix[col].clone();
+            for (_, line) in matrix.iter_mut().enumerate().filter(|(row, _)| *row != col)
+            {
+                let factor = line[col] / pivot_line[col];
+                line.iter_mut().zip(pivot_line.iter()).skip(col).for_each(|(x, &p)| *x = *x - factor * p);
+            }
+        }
+
+        // the weight of the last corner insures that the weights sum to one
+        let mut weights: Vec<CoordFloat> = matrix.iter().enumerate().map(|(i, line)| line[dim] / line[i]).collect();
+        let sum = weights.iter().copied().fold(CoordFloat::zero(), ::std::ops::Add::add);
+        weights.push(CoordFloat::one() - sum);
+        Some(weights)
+    }
+
+    /// takes a simplex and splits it around an arbitrary point given by its barycentric coordinates
+    /// the ratio of each child is the exact fraction of its father that it occupies
+    /// difference is the best value so far minus the worst value so far
+    pub fn split_at(self,
+                    new_point: Arc<Point<CoordFloat, ValueFloat>>,
+                    weights: &[CoordFloat],
+                    difference: ValueFloat)
+                    -> Vec<Self>
+    {
+        self.corners
+            .iter()
+            .zip(weights.iter())
+            .enumerate()
+            // we refuse flat simplex (which happens when the point is on a face of its father)
+            .filter(|(_, (_, &weight))| weight > Self::weight_tolerance())
+            .map(|(i, (_, &weight))| {
+                let mut corners = self.corners.clone();
+                corners[i] = new_point.clone();
+                let ratio = self.ratio
+                            * ValueFloat::from(weight).expect("Unable to convert from coordinate type to value type.");
+                Simplex::new(corners, ratio, difference)
+            })
+            .collect()
+    }
+
+    /// barycentric coordinates below this tolerance are considered to be zero
+    pub fn weight_tolerance() -> CoordFloat
+    {
+        CoordFloat::epsilon().sqrt()
+    }
+
+    /// returns the value of the center of the simplex, interpolated from its corners
+    pub fn interpolate(&self) -> ValueFloat
+    {
+        self.interpolate_by(|c| c.value)
+    }
+
+    /// returns the value of a corner as seen by the score of the simplex
+    /// infeasible corners are worse than the worst value, the more so the larger their violation
+    /// (relative to the largest violation, capped to one) which favours feasible regions
+    /// and, while no feasible point is known, the regions that are closest to being feasible
+    /// (the square root gives more resolution to small violations, close to the feasible region)
+    pub fn feasible_value(&self, corner: &Point<CoordFloat, ValueFloat>) -> ValueFloat
+    {
+        if corner.is_feasible()
+        {
+            corner.value
+        }
+        else
+        {
+            let relative_violation = (corner.violation / self.max_violation).min(ValueFloat::one()).sqrt();
+            self.min_value - self.difference * relative_violation
+        }
+    }
+
+    /// interpolates a quantity, known on the corners, at the center of the simplex
+    pub fn interpolate_by(&self, quantity: impl Fn(&Point<CoordFloat, ValueFloat>) -> ValueFloat) -> ValueFloat
     {
         // computes the inverse of the distance from the center to each corner
         let inverse_distances: Vec<ValueFloat> =
             self.corners
                 .iter()
-                .map(|c| ValueFloat::one() / Point::distance(&c.coordinates, &self.center))
+                .map(|c| ValueFloat::one() / c.distance_to(&self.center, None))
                 .collect();
         let total_inverse_distance: ValueFloat =
             inverse_distances.iter().copied().fold(ValueFloat::zero(), ::std::ops::Add::add);
 
-        // computes the value of the center, interpolated from the corners
-        let interpolated_value = self.corners
-                                     .iter()
-                                     .zip(inverse_distances.iter())
-                                     .map(|(c, &d)| c.value * d)
-                                     .fold(ValueFloat::zero(), ::std::ops::Add::add)
-                                 / total_inverse_distance;
-
-        // computes the number of split needed to reach the given ratio if we start from a regular simplex
-        let dim = ValueFloat::from(self.center.len()).unwrap();
-        let split_number = self.ratio.log(dim + ValueFloat::one()).abs();
+        // weighted average of the quantities of the corners
+        self.corners
+            .iter()
+            .zip(inverse_distances.iter())
+            .map(|(c, &d)| quantity(c) * d)
+            .fold(ValueFloat::zero(), ::std::ops::Add::add)
+        / total_inverse_distance
+    }
 
-        // insures that the difference (which is positiv by construction) is non zero
-        let difference = self.difference + Float::epsilon();
+    /// replaces a corner (identified by its address) with a new point
+    /// returns true if the corner was found in the simplex
+    pub fn replace_corner(&mut self,
+                          old_point: &Arc<Point<CoordFloat, ValueFloat>>,
+                          new_point: &Arc<Point<CoordFloat, ValueFloat>>)
+                          -> bool
+    {
+        match self.corners.iter_mut().find(|c| Arc::ptr_eq(c, old_point))
+        {
+            Some(corner) =>
+            {
+                *corner = new_point.clone();
+                true
+            }
+            None => false
+        }
+    }
 
-        interpolated_value - difference * (split_number / exploration_depth)
+    /// computes the number of split needed to reach the ratio of the simplex if we start from a regular simplex
+    pub fn split_number(&self) -> ValueFloat
+    {
+        let dim = ValueFloat::from(self.center.len()).unwrap();
+        self.ratio.log(dim + ValueFloat::one()).abs()
     }
 }
 
+/// returns all the permutations of `0..n`
+fn permutations(n: usize) -> Vec<Vec<usize>>
+{
+    (0..n).fold(vec![Vec::new()], |permutations, i| {
+              // inserts the new element at every position of the permutations of the previous elements
+              permutations.into_iter()
+                          .flat_map(|permutation| {
+                              (0..=permutation.len()).map(move |position| {
+                                                         let mut permutation = permutation.clone();
+                                                         permutation.insert(position, i);
+                                                         permutation
+                                                     })
+                          })
+                          .collect()
+          })
+}
+
 //-----------------------------------------------------------------------------
 // TRAITS FOR PRIORITY QUEUE
 
@@ -0,0 +1,167 @@
+use crate::ask_tell::*;
+use crate::scoring::*;
+use num_traits::Float;
+use std::time::{Duration, Instant};
+
+/// Describes when a search should stop, conditions can be combined with `and` / `or`.
+///
+/// When a search stops, the condition that fired is reported (see `Optimizer::stop_reason`).
+///
+/// ```rust
+/// # use simplers_optimization::{Optimizer, StopCondition};
+/// # use std::time::Duration;
+/// # use std::cell::Cell;
+/// # fn main() {
+/// let nb_evaluations = Cell::new(0);
+/// let f = |v:&[f64]| { nb_evaluations.set(nb_evaluations.get() + 1); v[0] * v[1] };
+/// let input_interval = vec![(-10., 10.), (-20., 20.)];
+///
+/// // stops after 200 evaluations, after a second or once the value goes below -150 without improving for 20 evaluations
+/// let stop_condition = StopCondition::MaxEvaluations(200)
+///                          .or(StopCondition::Timeout(Duration::from_secs(1)))
+///                          .or(StopCondition::TargetValue(-150.).and(StopCondition::NoImprovement(20)));
+///
+/// let (min_value, coordinates, stop_reason) = Optimizer::minimize_until(&f, &input_interval, stop_condition);
+///
+/// // the minimum, -200, is a corner of the search space and the search stops well before 200 evaluations
+/// assert_eq!(stop_reason, Some(StopCondition::TargetValue(-150.).and(StopCondition::NoImprovement(20))));
+/// assert!(nb_evaluations.get() < 200);
+/// assert_eq!(min_value, coordinates[0] * coordinates[1]);
+/// assert!(min_value <= -150.);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopCondition<ValueFloat>
+{
+    /// Stops once the function has been evaluated the given number of times
+    /// (including the d+1 evaluations used to initialize the search).
+    MaxEvaluations(usize),
+    /// Stops once the given time has elapsed since the beginning of the search.
+    ///
+    /// The condition is checked between evaluations and will not interrupt an evaluation in progress.
+    Timeout(Duration),
+    /// Stops once the best value found is at least as good as the given value.
+    TargetValue(ValueFloat),
+    /// Stops once the given number of evaluations have been performed without improving the best value.
+    NoImprovement(usize),
+    /// Stops once the smallest simplex left to explore covers less than the given fraction of the search space.
+    MinRatio(ValueFloat),
+    /// Stops once both conditions are met.
+    And(Box<StopCondition<ValueFloat>>, Box<StopCondition<ValueFloat>>),
+    /// Stops once one of the conditions is met.
+    Or(Box<StopCondition<ValueFloat>>, Box<StopCondition<ValueFloat>>)
+}
+
+/// progress of a search, used to check the stopping conditions
+pub(crate) struct Progress
+{
+    /// set when the first evaluation is performed
+    start: Option<Instant>,
+    nb_evaluations: usize,
+    nb_evaluations_without_improvement: usize
+}
+
+impl Progress
+{
+    /// creates the progress of a search that has not started yet
+    pub(crate) fn new() -> Self
+    {
+        Progress { start: None, nb_evaluations: 0, nb_evaluations_without_improvement: 0 }
+    }
+
+    /// starts the clock, if it is not already running
+    pub(crate) fn start(&mut self)
+    {
+        self.start.get_or_insert_with(Instant::now);
+    }
+
+    /// returns the number of evaluations performed so far
+    pub(crate) fn nb_evaluations(&self) -> usize
+    {
+        self.nb_evaluations
+    }
+
+    /// records an evaluation
+    pub(crate) fn record_evaluation(&mut self, is_improvement: bool)
+    {
+        self.nb_evaluations += 1;
+        self.nb_evaluations_without_improvement =
+            if is_improvement { 0 } else { self.nb_evaluations_without_improvement + 1 };
+    }
+}
+
+impl<ValueFloat: Float> StopCondition<ValueFloat>
+{
+    /// Combines two conditions, the search stops once both are met.
+    pub fn and(self, other: Self) -> Self
+    {
+        StopCondition::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combines two conditions, the search stops once one of them is met.
+    pub fn or(self, other: Self) -> Self
+    {
+        StopCondition::Or(Box::new(self), Box::new(other))
+    }
+
+    /// returns the fraction, between zero and one, of the budget of the condition that has been consumed
+    /// (None if the condition has no budget, such as a target value)
+    pub(crate) fn budget_fraction(&self, progress: &Progress) -> Option<f64>
+    {
+        let fraction = match self
+        {
+            StopCondition::MaxEvaluations(0) => 1.,
+            StopCondition::MaxEvaluations(nb_evaluations) => progress.nb_evaluations as f64 / *nb_evaluations as f64,
+            StopCondition::Timeout(duration) =>
+            {
+                let elapsed = progress.start.map_or(0., |start| start.elapsed().as_secs_f64());
+                if duration.is_zero() { 1. } else { elapsed / duration.as_secs_f64() }
+            }
+            StopCondition::TargetValue(_) | StopCondition::NoImprovement(_) | StopCondition::MinRatio(_) =>
+            {
+                return None
+            }
+            // both budgets need to be consumed
+            StopCondition::And(c1, c2) => c1.budget_fraction(progress)?.min(c2.budget_fraction(progress)?),
+            // the first budget to be consumed stops the search
+            StopCondition::Or(c1, c2) =>
+            {
+                match (c1.budget_fraction(progress), c2.budget_fraction(progress))
+                {
+                    (Some(f1), Some(f2)) => f1.max(f2),
+                    (f1, f2) => f1.or(f2)?
+                }
+            }
+        };
+        Some(fraction.min(1.))
+    }
+
+    /// returns the condition that fired, if any
+    /// (`Or` returns the sub-condition that fired while `And` returns itself)
+    pub(crate) fn check<CoordFloat, Scoring>(&self,
+                                             progress: &Progress,
+                                             search: &AskTellOptimizer<CoordFloat, ValueFloat, Scoring>)
+                                             -> Option<&Self>
+        where CoordFloat: Float,
+              Scoring: ScoringStrategy<CoordFloat, ValueFloat>
+    {
+        let is_met = match self
+        {
+            StopCondition::MaxEvaluations(nb_evaluations) => progress.nb_evaluations >= *nb_evaluations,
+            StopCondition::Timeout(duration) => progress.start.is_some_and(|start| start.elapsed() >= *duration),
+            StopCondition::TargetValue(target) =>
+            {
+                let target = search.search_space.orient(*target);
+                search.best_point.as_ref().is_some_and(|best_point| best_point.value >= target)
+            }
+            StopCondition::NoImprovement(nb_evaluations) =>
+            {
+                progress.nb_evaluations_without_improvement >= *nb_evaluations
+            }
+            StopCondition::MinRatio(ratio) => search.smallest_ratio().is_some_and(|smallest| smallest < *ratio),
+            StopCondition::And(c1, c2) => c1.check(progress, search).is_some() && c2.check(progress, search).is_some(),
+            StopCondition::Or(c1, c2) => return c1.check(progress, search).or_else(|| c2.check(progress, search))
+        };
+        Some(self).filter(|_| is_met)
+    }
+}